@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use counter::cpi::accounts::{IncrementCpi, IncrementPdaAuthority};
+use counter::program::Counter as CounterProgram;
+use counter::Counter;
+
+// Declare program ID (will be auto-generated when you build)
+declare_id!("9BckcS1UMrT2wRJ8KmNJWhF2KCGJAv3sVpWd82XTxqyh");
+
+/// Seed of the PDA this program signs with as a counter authority
+pub const AUTHORITY_SEED: &[u8] = b"caller-authority";
+
+#[program]
+pub mod counter_caller {
+    use super::*;
+
+    /// Increment a counter whose authority is this program's PDA
+    pub fn increment_via_cpi(ctx: Context<IncrementViaCpi>) -> Result<u64> {
+        let bump = [ctx.bumps.caller_authority];
+        let signer_seeds: &[&[&[u8]]] = &[&[AUTHORITY_SEED, &bump]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.counter_program.to_account_info(),
+            IncrementCpi {
+                counter: ctx.accounts.counter.to_account_info(),
+                authority: ctx.accounts.caller_authority.to_account_info(),
+                config: ctx.accounts.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        let count = counter::cpi::increment_cpi(cpi_ctx)?.get();
+
+        msg!("Counter incremented via CPI to: {}", count);
+        Ok(count)
+    }
+
+    /// Increment through the counter's PDA-authority path, passing the seeds
+    /// that derive this program's authority PDA
+    pub fn increment_as_pda_authority(ctx: Context<IncrementViaCpi>) -> Result<u64> {
+        let bump = [ctx.bumps.caller_authority];
+        let signer_seeds: &[&[&[u8]]] = &[&[AUTHORITY_SEED, &bump]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.counter_program.to_account_info(),
+            IncrementPdaAuthority {
+                counter: ctx.accounts.counter.to_account_info(),
+                authority: ctx.accounts.caller_authority.to_account_info(),
+                config: ctx.accounts.config.to_account_info(),
+            },
+            signer_seeds,
+        );
+        let count = counter::cpi::increment_pda_authority(
+            cpi_ctx,
+            crate::ID,
+            vec![AUTHORITY_SEED.to_vec(), bump.to_vec()],
+        )?
+        .get();
+
+        msg!("Counter incremented by PDA authority to: {}", count);
+        Ok(count)
+    }
+}
+
+/// Context for increment_via_cpi and increment_as_pda_authority
+#[derive(Accounts)]
+pub struct IncrementViaCpi<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+
+    /// CHECK: PDA that signs as the counter authority; holds no data
+    #[account(seeds = [AUTHORITY_SEED], bump)]
+    pub caller_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The counter program's config, validated by the counter program
+    #[account(mut, seeds = [b"config"], bump, seeds::program = counter_program.key())]
+    pub config: UncheckedAccount<'info>,
+
+    pub counter_program: Program<'info, CounterProgram>,
+}
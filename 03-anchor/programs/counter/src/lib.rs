@@ -1,104 +1,6227 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{
+    ed25519_program,
+    hash::{hash, hashv},
+    instruction::Instruction,
+    program::{invoke, invoke_signed},
+    sysvar::instructions::{load_current_index_checked, load_instruction_at_checked},
+};
+use anchor_lang::{system_program, Discriminator};
 
 // Declare program ID (will be auto-generated when you build)
 declare_id!("GK9MqqiyWWThZHsQwcnvmZHZY5KoGn3sdg9ii8xocidr");
 
+/// Maximum length of a counter label, in bytes
+pub const MAX_LABEL_LEN: usize = 32;
+
+/// Maximum length of a counter note, in bytes
+pub const MAX_NOTE_LEN: usize = 200;
+
+/// Maximum length of a single counter tag, in bytes
+pub const MAX_TAG_LEN: usize = 32;
+
+/// Maximum number of tags on a counter
+pub const MAX_TAGS: usize = 16;
+
+/// Maximum length of an increment memo, in bytes
+pub const MAX_MEMO_LEN: usize = 128;
+
+/// Maximum number of deltas accepted by `apply_deltas`
+pub const MAX_DELTAS: usize = 32;
+
+/// Bytes allocated for a new counter; exported so clients can size accounts
+#[constant]
+pub const COUNTER_LEN: usize = Counter::LEN;
+
+/// Maximum number of counters a batch instruction handles in one call, so
+/// oversized batches fail up front instead of running out of compute
+pub const MAX_BATCH: usize = 10;
+
+/// Maximum number of counters read by `read_many`; the returned `Vec<u64>`
+/// must fit in 1024 bytes of return data
+pub const MAX_READ_MANY: usize = (1024 - 4) / 8;
+
+/// Maximum number of counters scanned by `filter_by_authority`; the
+/// returned `Vec<Pubkey>` must fit in 1024 bytes of return data
+pub const MAX_FILTER_ACCOUNTS: usize = (1024 - 4) / 32;
+
+/// Maximum number of keys in a counter's `delegates` list
+pub const MAX_DELEGATES: usize = 5;
+
+/// Maximum number of increments queued by `schedule_increment`
+pub const MAX_SCHEDULED: usize = 8;
+
+/// Length of the UTC day `daily_cap` resets on
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Number of recent values kept in a counter's history ring buffer
+pub const HISTORY_LEN: usize = 8;
+
+/// Maximum number of counters listed in an authority's registry
+pub const MAX_REGISTRY_COUNTERS: usize = 16;
+
+/// SPL Token program, invoked directly for token burns
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// SPL Memo program, whose instruction `increment_with_proof` requires
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Number of co-owner slots available for threshold approvals
+pub const MAX_AUTHORITIES: usize = 3;
+
+/// Maximum number of payees in the config's fee split
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+
+/// Maximum number of authorities in the config's creation allowlist
+pub const MAX_ALLOWLIST: usize = 16;
+
+/// Maximum number of extra readers on a private counter
+pub const MAX_READERS: usize = 8;
+
+/// Basis points in a whole; fee split shares must sum to this
+pub const TOTAL_BPS: u16 = 10_000;
+
+/// Account layout version written by this program; bumped by `migrate`
+pub const COUNTER_VERSION: u8 = 2;
+
 #[program]
 pub mod counter {
     use super::*;
 
-    /// Initialize a new counter account
-    pub fn initialize(ctx: Context<Initialize>, initial_value: u64) -> Result<()> {
-        let counter = &mut ctx.accounts.counter;
-        counter.authority = ctx.accounts.authority.key();
-        counter.count = initial_value;
-        counter.last_updated = Clock::get()?.unix_timestamp;
-        
-        msg!("Counter initialized with value: {}", initial_value);
-        Ok(())
-    }
+    /// Initialize a new counter account
+    ///
+    /// Settings left as `None` in `params` fall back to the global config's
+    /// defaults. Passing one of the
+    /// authority's groups makes it the counter's `parent`.
+    pub fn initialize(
+        ctx: Context<Initialize>,
+        initial_value: u64,
+        params: InitializeParams,
+    ) -> Result<()> {
+        init_counter(
+            &mut ctx.accounts.counter,
+            ctx.accounts.authority.key(),
+            initial_value,
+            params,
+            &mut ctx.accounts.config,
+        )?;
+        if let Some(group) = &ctx.accounts.group {
+            ctx.accounts.counter.parent = group.key();
+        }
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Create the authority's counter PDA with a bounty, funding its vault
+    /// with `vault_lamports` in the same instruction
+    ///
+    /// The vault must end up rent-exempt, so `vault_lamports` has to cover
+    /// at least the rent-exempt minimum of an empty account.
+    pub fn initialize_with_bounty(
+        ctx: Context<InitializeWithBounty>,
+        initial_value: u64,
+        bounty_per: u64,
+        vault_lamports: u64,
+    ) -> Result<()> {
+        require!(vault_lamports > 0, ErrorCode::InvalidAmount);
+
+        init_counter(
+            &mut ctx.accounts.counter,
+            ctx.accounts.authority.key(),
+            initial_value,
+            InitializeParams::default(),
+            &mut ctx.accounts.config,
+        )?;
+        ctx.accounts.counter.bounty_lamports = bounty_per;
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.bounty_vault.to_account_info(),
+                },
+            ),
+            vault_lamports,
+        )?;
+        assert_rent_exempt(&ctx.accounts.bounty_vault.to_account_info())?;
+
+        msg!(
+            "Bounty of {} lamports per increment funded with {} lamports",
+            bounty_per,
+            vault_lamports
+        );
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Create a counter for an authority holding the config's gate mint
+    ///
+    /// `token_account` must be an SPL token account for `config.gate_mint`
+    /// owned by the authority with a balance of at least one.
+    pub fn initialize_gated(ctx: Context<InitializeGated>, initial_value: u64) -> Result<()> {
+        let balance = token_balance(
+            &ctx.accounts.token_account,
+            &ctx.accounts.config.gate_mint,
+            &ctx.accounts.authority.key(),
+        )?;
+        require!(balance >= 1, ErrorCode::NotTokenHolder);
+
+        init_counter(
+            &mut ctx.accounts.counter,
+            ctx.accounts.authority.key(),
+            initial_value,
+            InitializeParams::default(),
+            &mut ctx.accounts.config,
+        )?;
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Create a copy of `source` at `[b"clone", authority, [new_seed]]`
+    ///
+    /// The value and settings are copied: bounds, rate limits, caps,
+    /// breaker, milestones, expiry, timers, delegates, co-owners and
+    /// readers. State tied to the source address (bounty, escrow target,
+    /// group, votes, pending transfers, scheduled increments and the
+    /// `CounterMeta` account) is not, and stats such as history, the
+    /// accumulator and increment totals start fresh. The two counters are
+    /// independent afterwards.
+    pub fn clone_counter(ctx: Context<CloneCounter>, new_seed: u8) -> Result<()> {
+        let source = &ctx.accounts.source;
+        let params = InitializeParams {
+            max_value: Some(source.max_value),
+            min_interval_seconds: Some(source.min_interval_seconds),
+            min_value: Some(source.min_value),
+            wrap: Some(source.wrap),
+            freeze_authority: Some(source.freeze_authority),
+            decay_per_second: Some(source.decay_per_second),
+            cosigner: Some(source.cosigner),
+            max_change_bps: Some(source.max_change_bps),
+            overflow_policy: Some(source.effective_overflow_policy()),
+            password_hash: Some(source.password_hash),
+            max_transfers: Some(source.max_transfers),
+            decimals: Some(source.decimals),
+        };
+        let count = source.count;
+
+        init_counter(
+            &mut ctx.accounts.counter,
+            ctx.accounts.authority.key(),
+            count,
+            params,
+            &mut ctx.accounts.config,
+        )?;
+        let source = &ctx.accounts.source;
+        let counter = &mut ctx.accounts.counter;
+        counter.delegate = source.delegate;
+        counter.delegates = source.delegates.clone();
+        counter.authorities = source.authorities;
+        counter.threshold = source.threshold;
+        counter.readers = source.readers.clone();
+        counter.private = source.private;
+        counter.count_on_read = source.count_on_read;
+        counter.milestones = source.milestones;
+        counter.milestones_fired = source.milestones_fired;
+        counter.bucket_capacity = source.bucket_capacity;
+        counter.refill_per_second = source.refill_per_second;
+        counter.tokens = source.bucket_capacity;
+        counter.last_refill = counter.last_updated;
+        counter.max_per_window = source.max_per_window;
+        counter.window_seconds = source.window_seconds;
+        counter.cooldown_seconds = source.cooldown_seconds;
+        counter.daily_cap = source.daily_cap;
+        counter.expires_after = source.expires_after;
+        counter.end_ts = source.end_ts;
+        counter.transfer_timelock = source.transfer_timelock;
+        counter.vote_mint = source.vote_mint;
+
+        msg!("Cloned counter {} with seed {}", ctx.accounts.source.key(), new_seed);
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Create the authority's counter PDA if it does not exist yet and return
+    /// its current count
+    ///
+    /// An existing counter is left untouched, so `initial_value` only applies
+    /// on creation.
+    pub fn initialize_or_get(ctx: Context<InitializeOrGet>, initial_value: u64) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        if counter.authority == Pubkey::default() {
+            init_counter(
+                counter,
+                ctx.accounts.authority.key(),
+                initial_value,
+                InitializeParams::default(),
+                &mut ctx.accounts.config,
+            )?;
+            register_counter(
+                &mut ctx.accounts.registry,
+                ctx.accounts.authority.key(),
+                counter.key(),
+                ctx.bumps.registry,
+            )?;
+        } else {
+            msg!("Counter already initialized with value: {}", counter.count);
+        }
+        Ok(counter.count)
+    }
+
+    /// Initialize a counter at a fresh keypair address instead of the PDA
+    ///
+    /// Keypair counters are not tied to the authority's seed, so a single
+    /// authority can own several of them, up to `MAX_REGISTRY_COUNTERS`
+    /// counters in its registry. Parameters match `initialize`.
+    pub fn initialize_keypair(
+        ctx: Context<InitializeKeypair>,
+        initial_value: u64,
+        params: InitializeParams,
+    ) -> Result<()> {
+        init_counter(
+            &mut ctx.accounts.counter,
+            ctx.accounts.authority.key(),
+            initial_value,
+            params,
+            &mut ctx.accounts.config,
+        )?;
+        if let Some(group) = &ctx.accounts.group {
+            ctx.accounts.counter.parent = group.key();
+        }
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Create `count` indexed counters in one instruction
+    ///
+    /// Counter `i` lives at the PDA `[b"counter", authority, [i]]` and must be
+    /// passed, in index order, in `remaining_accounts`.
+    pub fn batch_initialize<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchInitialize<'info>>,
+        count: u8,
+        initial_value: u64,
+    ) -> Result<()> {
+        require!(count as usize <= MAX_BATCH, ErrorCode::BatchTooLarge);
+        require!(
+            ctx.remaining_accounts.len() == count as usize,
+            ErrorCode::AccountCountMismatch
+        );
+
+        let authority = ctx.accounts.authority.key();
+        let rent = Rent::get()?.minimum_balance(Counter::LEN);
+
+        for (index, account_info) in ctx.remaining_accounts.iter().enumerate() {
+            let index = [index as u8];
+            let (expected, bump) = Pubkey::find_program_address(
+                &[b"counter", authority.as_ref(), &index],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                account_info.key(),
+                expected,
+                ErrorCode::InvalidCounterAddress
+            );
+
+            system_program::create_account(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::CreateAccount {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                    &[&[b"counter", authority.as_ref(), &index, &[bump]]],
+                ),
+                rent,
+                Counter::LEN as u64,
+                &crate::ID,
+            )?;
+
+            let mut counter = Account::<Counter>::try_from_unchecked(account_info)?;
+            init_counter(
+                &mut counter,
+                authority,
+                initial_value,
+                InitializeParams::default(),
+                &mut ctx.accounts.config,
+            )?;
+            counter.exit(&crate::ID)?;
+
+            register_counter(
+                &mut ctx.accounts.registry,
+                authority,
+                expected,
+                ctx.bumps.registry,
+            )?;
+        }
+
+        msg!("Initialized {} counters", count);
+        Ok(())
+    }
+
+    /// Create the program-wide config; the caller becomes its admin
+    ///
+    /// Can only succeed once since the config lives at a fixed PDA, so
+    /// deployers should run it immediately after deploying the program.
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        default_max_value: u64,
+        default_min_interval: i64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.admin = ctx.accounts.admin.key();
+        config.treasury = config.admin;
+        config.default_max_value = default_max_value;
+        config.default_min_interval = default_min_interval;
+        config.bump = ctx.bumps.config;
+
+        msg!("Config initialized with admin: {}", config.admin);
+        Ok(())
+    }
+
+    /// Update the default counter settings (admin only)
+    pub fn update_config(
+        ctx: Context<UpdateConfig>,
+        default_max_value: u64,
+        default_min_interval: i64,
+    ) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.default_max_value = default_max_value;
+        config.default_min_interval = default_min_interval;
+
+        msg!(
+            "Config updated: default_max_value={}, default_min_interval={}",
+            default_max_value,
+            default_min_interval
+        );
+        Ok(())
+    }
+
+    /// Set the account that collects priority tips (admin only)
+    pub fn set_treasury(ctx: Context<UpdateConfig>, treasury: Pubkey) -> Result<()> {
+        ctx.accounts.config.treasury = treasury;
+
+        msg!("Treasury set to: {}", treasury);
+        Ok(())
+    }
+
+    /// Set the mint whose holders may use `initialize_gated` (admin only)
+    pub fn set_gate_mint(ctx: Context<UpdateConfig>, gate_mint: Pubkey) -> Result<()> {
+        ctx.accounts.config.gate_mint = gate_mint;
+
+        msg!("Gate mint set to: {}", gate_mint);
+        Ok(())
+    }
+
+    /// Set the payees and basis-point shares used by `increment_paid`
+    /// (admin only); shares must sum to 10000
+    pub fn set_fee_recipients(
+        ctx: Context<UpdateConfig>,
+        fee_recipients: Vec<FeeRecipient>,
+    ) -> Result<()> {
+        require!(
+            fee_recipients.len() <= MAX_FEE_RECIPIENTS,
+            ErrorCode::InvalidSplit
+        );
+        validate_fee_split(&fee_recipients)?;
+        ctx.accounts.config.fee_recipients = fee_recipients;
+
+        msg!(
+            "Fee split set across {} recipients",
+            ctx.accounts.config.fee_recipients.len()
+        );
+        Ok(())
+    }
+
+    /// Allow `authority` to create counters (admin only)
+    ///
+    /// While the allowlist is empty anyone may create counters.
+    pub fn add_to_allowlist(ctx: Context<UpdateConfig>, authority: Pubkey) -> Result<()> {
+        let allowlist = &mut ctx.accounts.config.allowlist;
+        if !allowlist.contains(&authority) {
+            require!(allowlist.len() < MAX_ALLOWLIST, ErrorCode::AllowlistFull);
+            allowlist.push(authority);
+        }
+
+        msg!("Added to allowlist: {}", authority);
+        Ok(())
+    }
+
+    /// Stop `authority` from creating counters (admin only)
+    pub fn remove_from_allowlist(ctx: Context<UpdateConfig>, authority: Pubkey) -> Result<()> {
+        ctx.accounts.config.allowlist.retain(|key| key != &authority);
+
+        msg!("Removed from allowlist: {}", authority);
+        Ok(())
+    }
+
+    /// Freeze or unfreeze every counter at once (admin only)
+    ///
+    /// Enforced by the mutating instructions whenever the config account is
+    /// passed to them.
+    pub fn set_global_pause(ctx: Context<UpdateConfig>, paused: bool) -> Result<()> {
+        ctx.accounts.config.global_pause = paused;
+
+        msg!("Global pause set to: {}", paused);
+        Ok(())
+    }
+
+    /// Increment the counter, returning the new count
+    pub fn increment(ctx: Context<Increment>) -> Result<u64> {
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        let wrapped = counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        if wrapped {
+            msg!("Counter wrapped to: {}", counter.count);
+            emit!(CounterWrapped {
+                counter: counter.key(),
+                authority: counter.authority,
+                count: counter.count,
+                timestamp: counter.last_updated,
+            });
+        }
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(counter.count)
+    }
+
+    /// Start a new voting round weighted by balances of `mint`
+    ///
+    /// Every holder may vote once per round; receipts from earlier rounds
+    /// no longer count.
+    pub fn open_vote_round(ctx: Context<OpenVoteRound>, mint: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        counter.vote_mint = mint;
+        counter.vote_round = counter.vote_round.checked_add(1).ok_or(ErrorCode::AddOverflow)?;
+
+        msg!("Vote round {} opened for mint: {}", counter.vote_round, mint);
+        Ok(())
+    }
+
+    /// Add the voter's balance of the round's mint to the tally
+    pub fn vote(ctx: Context<Vote>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        require!(counter.vote_round > 0, ErrorCode::VotingClosed);
+
+        let receipt = &mut ctx.accounts.receipt;
+        require!(receipt.round != counter.vote_round, ErrorCode::AlreadyVoted);
+
+        let voter = ctx.accounts.voter.key();
+        let weight = token_balance(&ctx.accounts.token_account, &counter.vote_mint, &voter)?;
+        require!(weight > 0, ErrorCode::NotTokenHolder);
+
+        record_increment(&mut ctx.accounts.config);
+        // Votes from different holders are not subject to the rate limit
+        counter.apply_increment_unthrottled(weight, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = voter;
+
+        receipt.counter = counter.key();
+        receipt.voter = voter;
+        receipt.round = counter.vote_round;
+        receipt.bump = ctx.bumps.receipt;
+
+        msg!("Vote of weight {} recorded, tally: {}", weight, counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter, but only once the clock reaches `unlock_ts`
+    pub fn increment_after(ctx: Context<Increment>, unlock_ts: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= unlock_ts, ErrorCode::TooEarly);
+
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_increment(1, now)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter unless the clock is already past `deadline_ts`
+    ///
+    /// A transaction that lands late then fails instead of applying after
+    /// the caller stopped expecting it.
+    pub fn increment_before(ctx: Context<Increment>, deadline_ts: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline_ts, ErrorCode::DeadlineExceeded);
+
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_increment(1, now)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter for any signer who knows its password
+    ///
+    /// The password is compared by SHA-256 hash, but it travels in the
+    /// transaction in plain text, so this is only a casual gate.
+    pub fn increment_with_password(
+        ctx: Context<IncrementWithPassword>,
+        password: String,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        require!(
+            counter.password_hash != [0u8; 32]
+                && hash(password.as_bytes()).to_bytes() == counter.password_hash,
+            ErrorCode::WrongPassword
+        );
+
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.signer.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter only if it still holds `expected`
+    ///
+    /// Compare-and-swap for clients racing on the same counter: a stale read
+    /// fails with `UnexpectedValue` and the client can re-fetch and retry.
+    pub fn increment_if(ctx: Context<Increment>, expected: u64) -> Result<()> {
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        require!(counter.count == expected, ErrorCode::UnexpectedValue);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented from {} to: {}", expected, counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter only if a sibling counter of the same
+    /// authority has reached `threshold`
+    pub fn increment_if_other(ctx: Context<IncrementIfOther>, threshold: u64) -> Result<()> {
+        require!(
+            ctx.accounts.other.count >= threshold,
+            ErrorCode::ConditionNotMet
+        );
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the authority's counter PDA using a caller-supplied bump
+    ///
+    /// A non-canonical `bump` fails with `InvalidBump` rather than a seeds
+    /// mismatch, so CPI callers can tell a stale bump from a wrong counter.
+    pub fn increment_with_bump(ctx: Context<IncrementWithBump>, bump: u8) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {} (bump {})", counter.count, bump);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Stage an increment of one, to be applied by `finalize` or dropped by
+    /// `cancel_pending`
+    ///
+    /// Only one increment may be pending at a time. Guards such as rate
+    /// limits and the cap are checked when it is finalized.
+    pub fn stage_increment(ctx: Context<PendingIncrement>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        require!(counter.pending_delta == 0, ErrorCode::PendingExists);
+        counter.pending_delta = 1;
+
+        msg!("Increment staged");
+        Ok(())
+    }
+
+    /// Apply the pending increment to the count
+    pub fn finalize(ctx: Context<PendingIncrement>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        let delta = counter.pending_delta;
+        require!(delta > 0, ErrorCode::NoPendingIncrement);
+
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(delta, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.pending_delta = 0;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Pending increment finalized, count: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Discard the pending increment without changing the count
+    pub fn cancel_pending(ctx: Context<PendingIncrement>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        require!(counter.pending_delta > 0, ErrorCode::NoPendingIncrement);
+        counter.pending_delta = 0;
+
+        msg!("Pending increment cancelled");
+        Ok(())
+    }
+
+    /// Increment the counter on behalf of another program, returning the new
+    /// count
+    ///
+    /// Intended for CPI: `authority` may be a PDA of the calling program that
+    /// signs with `invoke_signed`. Expected accounts are the counter
+    /// (writable), the authority or delegate (signer) and optionally the
+    /// config PDA. No bounty is paid on this path.
+    pub fn increment_cpi(ctx: Context<IncrementCpi>) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented via CPI to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(counter.count)
+    }
+
+    /// Increment a counter whose authority is a PDA of `authority_program`
+    ///
+    /// `seeds` (bump included) must derive the authority under
+    /// `authority_program`, so a wallet authority is rejected here. Since
+    /// only the owning program can sign for its PDA via `invoke_signed`, the
+    /// signer check then proves the call came from that program.
+    pub fn increment_pda_authority(
+        ctx: Context<IncrementPdaAuthority>,
+        authority_program: Pubkey,
+        seeds: Vec<Vec<u8>>,
+    ) -> Result<u64> {
+        let seed_slices: Vec<&[u8]> = seeds.iter().map(Vec::as_slice).collect();
+        let derived = Pubkey::create_program_address(&seed_slices, &authority_program)
+            .map_err(|_| error!(ErrorCode::InvalidPdaAuthority))?;
+        require_keys_eq!(
+            derived,
+            ctx.accounts.authority.key(),
+            ErrorCode::InvalidPdaAuthority
+        );
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented by PDA authority to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(counter.count)
+    }
+
+    /// Pay `tip_lamports` from the signer to the config treasury, then
+    /// increment the counter
+    pub fn increment_priority(
+        ctx: Context<IncrementPriority>,
+        tip_lamports: u64,
+    ) -> Result<()> {
+        require!(tip_lamports > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.authority.lamports() >= tip_lamports,
+            ErrorCode::InsufficientFunds
+        );
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.authority.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            tip_lamports,
+        )?;
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!(
+            "Counter incremented to {} with a tip of {} lamports",
+            counter.count,
+            tip_lamports
+        );
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Split `amount` lamports from the signer across the config's fee
+    /// recipients, then increment the counter
+    ///
+    /// The recipients must be passed in `remaining_accounts` in config
+    /// order. Rounding dust goes to the last recipient.
+    pub fn increment_paid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, IncrementPaid<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(
+            ctx.accounts.authority.lamports() >= amount,
+            ErrorCode::InsufficientFunds
+        );
+
+        let fee_recipients = &ctx.accounts.config.fee_recipients;
+        validate_fee_split(fee_recipients)?;
+        require!(
+            ctx.remaining_accounts.len() == fee_recipients.len(),
+            ErrorCode::AccountCountMismatch
+        );
+
+        let mut remaining = amount;
+        for (index, (recipient, account_info)) in fee_recipients
+            .iter()
+            .zip(ctx.remaining_accounts.iter())
+            .enumerate()
+        {
+            require_keys_eq!(
+                account_info.key(),
+                recipient.recipient,
+                ErrorCode::InvalidFeeRecipient
+            );
+
+            let share = if index == fee_recipients.len() - 1 {
+                remaining
+            } else {
+                // bps <= 10000, so the product fits in u128 and the share in u64
+                (amount as u128 * recipient.bps as u128 / TOTAL_BPS as u128) as u64
+            };
+            remaining -= share;
+
+            system_program::transfer(
+                CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.authority.to_account_info(),
+                        to: account_info.clone(),
+                    },
+                ),
+                share,
+            )?;
+        }
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!(
+            "Counter incremented to {} after paying {} lamports",
+            counter.count,
+            amount
+        );
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter even inside the rate-limit window
+    ///
+    /// Authority-only (delegates cannot force); caps and overflow still apply.
+    pub fn increment_force(ctx: Context<IncrementForce>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment_unthrottled(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter force-incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter with a 1-in-`one_in` chance, returning whether
+    /// it hit; a miss only logs
+    ///
+    /// Entropy is the most recent slot hash mixed with the counter and
+    /// signer. Validators can see and influence slot hashes and callers can
+    /// retry until they hit, so this is not fit for anything of value.
+    pub fn increment_random(ctx: Context<IncrementRandom>, one_in: u64) -> Result<bool> {
+        require!(one_in > 0, ErrorCode::InvalidOdds);
+
+        // SlotHashes is too large to deserialize on-chain; the newest entry's
+        // hash sits after the u64 length and that entry's u64 slot
+        let slot_hashes = ctx.accounts.slot_hashes.try_borrow_data()?;
+        let recent_hash = slot_hashes
+            .get(16..48)
+            .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+
+        let counter = &mut ctx.accounts.counter;
+        let seed = hashv(&[
+            recent_hash,
+            counter.key().as_ref(),
+            ctx.accounts.authority.key().as_ref(),
+            &counter.count.to_le_bytes(),
+        ]);
+        let roll = u64::from_le_bytes(seed.to_bytes()[..8].try_into().unwrap());
+
+        if roll % one_in != 0 {
+            msg!("Random increment missed (1 in {})", one_in);
+            return Ok(false);
+        }
+
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter randomly incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(true)
+    }
+
+    /// Increment the counter, then mint `amount` reward tokens to the
+    /// signer's token account
+    ///
+    /// The reward mint's authority must be the counter's
+    /// `[b"reward-authority", counter]` PDA, so only its increments can mint.
+    pub fn increment_mint_reward(ctx: Context<IncrementMintReward>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        // The reward goes to the signer, never to someone else's account
+        token_balance(
+            &ctx.accounts.token_account,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.authority.key(),
+        )?;
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        let counter_key = counter.key();
+        lock_counter(counter)?;
+        mint_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.mint,
+            &ctx.accounts.token_account,
+            &ctx.accounts.mint_authority,
+            &[
+                b"reward-authority",
+                counter_key.as_ref(),
+                &[ctx.bumps.mint_authority],
+            ],
+            amount,
+        )?;
+        counter.locked = false;
+
+        msg!("Counter incremented to {}, minted {} reward tokens", counter.count, amount);
+        emit!(CounterIncremented {
+            counter: counter_key,
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter, stopping at `u64::MAX` instead of erroring
+    pub fn increment_saturating(ctx: Context<Increment>) -> Result<()> {
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        let previous = counter.count;
+        counter.apply_increment_saturating(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        if counter.count == previous {
+            msg!("Counter saturated at: {}", counter.count);
+        } else {
+            msg!("Counter incremented to: {}", counter.count);
+        }
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter, failing if the new value would be above
+    /// `max_acceptable`
+    ///
+    /// Lets a client act on the count it last saw without racing other
+    /// writers past the value it expected.
+    pub fn increment_max(ctx: Context<Increment>, max_acceptable: u64) -> Result<()> {
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        if counter.count > max_acceptable {
+            msg!("Value {} exceeds max_acceptable {}", counter.count, max_acceptable);
+            return err!(ErrorCode::ExceedsMaxAcceptable);
+        }
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment on the authority's behalf using an off-chain signature
+    ///
+    /// The transaction must include an Ed25519 program instruction directly
+    /// before this one, verifying `signature` by the counter authority over
+    /// `message`. The message must be the counter address followed by the
+    /// current `nonce` (u64, little endian), which is bumped on success so
+    /// the same signature cannot be replayed. Anyone may relay it and pay fees.
+    pub fn increment_signed(
+        ctx: Context<IncrementSigned>,
+        signature: [u8; 64],
+        message: Vec<u8>,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+
+        let mut expected = counter.key().to_bytes().to_vec();
+        expected.extend_from_slice(&counter.nonce.to_le_bytes());
+        require!(message == expected, ErrorCode::InvalidSignedMessage);
+
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let current_index = load_current_index_checked(&instructions)?;
+        require!(current_index > 0, ErrorCode::MissingSignatureInstruction);
+        let ed25519_ix = load_instruction_at_checked(
+            (current_index - 1) as usize,
+            &instructions,
+        )?;
+        verify_ed25519_instruction(&ed25519_ix, &counter.authority, &signature, &message)?;
+
+        counter.nonce = counter.nonce
+            .checked_add(1)
+            .ok_or(ErrorCode::AddOverflow)?;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = counter.authority;
+
+        msg!("Counter incremented by signed message to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter, requiring an SPL Memo instruction earlier in
+    /// the same transaction
+    ///
+    /// The memo's contents are not checked, only that it is present, so bots
+    /// calling the program directly must also compose the memo instruction.
+    pub fn increment_with_proof(ctx: Context<IncrementWithProof>) -> Result<()> {
+        let instructions = ctx.accounts.instructions.to_account_info();
+        let current_index = load_current_index_checked(&instructions)? as usize;
+        let mut found = false;
+        for index in 0..current_index {
+            let ix = load_instruction_at_checked(index, &instructions)?;
+            if ix.program_id == MEMO_PROGRAM_ID {
+                found = true;
+                break;
+            }
+        }
+        require!(found, ErrorCode::MissingRequiredInstruction);
+
+        let counter = &mut ctx.accounts.counter;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented with proof to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Read the count, first incrementing it when `count_on_read` is set
+    ///
+    /// Anyone may call this, as with a page view. The increment is subject
+    /// to the counter's rate limits, so a read inside the interval fails.
+    pub fn read_and_count(ctx: Context<ReadAndCount>) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        if counter.count_on_read {
+            record_increment(&mut ctx.accounts.config);
+            counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+            emit_reached_milestones(counter);
+            counter.last_modifier = ctx.accounts.reader.key();
+
+            msg!("Counter read and incremented to: {}", counter.count);
+            emit!(CounterIncremented {
+                counter: counter.key(),
+                authority: counter.authority,
+                count: counter.count,
+                timestamp: counter.last_updated,
+            });
+        }
+        Ok(counter.count)
+    }
+
+    /// Increment the counter and log an audit memo alongside it
+    ///
+    /// The memo is only logged and emitted, never stored on the account.
+    pub fn increment_with_memo(ctx: Context<Increment>, memo: String) -> Result<()> {
+        require!(memo.len() <= MAX_MEMO_LEN, ErrorCode::MemoTooLong);
+
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter incremented to: {}", counter.count);
+        msg!("Memo: {}", memo);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        emit!(IncrementMemo {
+            counter: counter.key(),
+            signer: ctx.accounts.authority.key(),
+            count: counter.count,
+            memo,
+        });
+        Ok(())
+    }
+
+    /// Increment every counter passed in `remaining_accounts` by one
+    ///
+    /// Each account must be a writable counter owned by this program whose
+    /// authority is the signer; any failure aborts the whole batch. Returns
+    /// the number of counters incremented. A single `BatchIncremented` event
+    /// lists every counter instead of one event per counter.
+    pub fn batch_increment<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchIncrement<'info>>,
+    ) -> Result<u64> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH,
+            ErrorCode::BatchTooLarge
+        );
+        let authority = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+        let mut entries = Vec::with_capacity(ctx.remaining_accounts.len());
+
+        for account_info in ctx.remaining_accounts.iter() {
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+            // Verifies program ownership and the account discriminator
+            let mut counter = Account::<Counter>::try_from(account_info)?;
+            require!(counter.can_increment(&authority), ErrorCode::Unauthorized);
+            require!(!counter.requires_cosigner(), ErrorCode::CosignerRequired);
+
+            record_increment(&mut ctx.accounts.config);
+            counter.apply_increment(1, now)?;
+            emit_reached_milestones(&mut counter);
+            counter.last_modifier = authority;
+            counter.exit(&crate::ID)?;
+
+            entries.push(CounterValue {
+                counter: counter.key(),
+                count: counter.count,
+            });
+        }
+
+        let incremented = entries.len() as u64;
+        emit!(BatchIncremented {
+            authority,
+            entries,
+            timestamp: now,
+        });
+
+        msg!("Batch incremented {} counters", incremented);
+        Ok(incremented)
+    }
+
+    /// Decrement the counter, returning the new count
+    pub fn decrement(ctx: Context<Decrement>) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_decrement(1, Clock::get()?.unix_timestamp)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+        
+        msg!("Counter decremented to: {}", counter.count);
+        emit!(CounterDecremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(counter.count)
+    }
+
+    /// Double the counter, treating zero as one so growth can start
+    pub fn increment_geometric(ctx: Context<Increment>) -> Result<()> {
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        let now = Clock::get()?.unix_timestamp;
+        counter.apply_decay(now);
+        let target = if counter.count == 0 {
+            1
+        } else {
+            counter.count.checked_mul(2).ok_or_else(|| {
+                msg!("Doubling {} overflows u64", counter.count);
+                ErrorCode::MulOverflow
+            })?
+        };
+        let amount = target - counter.count;
+        counter.apply_increment(amount, now)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter doubled to: {}", counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Increment the counter by an arbitrary amount
+    pub fn increment_by(ctx: Context<Increment>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        pay_bounty(ctx.accounts, ctx.bumps.bounty_vault)?;
+        record_increment(&mut ctx.accounts.config);
+        let counter = &mut ctx.accounts.counter;
+        let wrapped = counter.apply_increment(amount, Clock::get()?.unix_timestamp)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        if wrapped {
+            msg!("Counter wrapped to: {}", counter.count);
+            emit!(CounterWrapped {
+                counter: counter.key(),
+                authority: counter.authority,
+                count: counter.count,
+                timestamp: counter.last_updated,
+            });
+        }
+
+        msg!("Counter incremented by {} to: {}", amount, counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Decrement the counter by an arbitrary amount
+    pub fn decrement_by(ctx: Context<Decrement>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        let counter = &mut ctx.accounts.counter;
+        counter.apply_decrement(amount, Clock::get()?.unix_timestamp)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter decremented by {} to: {}", amount, counter.count);
+        emit!(CounterDecremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Decrement the counter by one after burning `amount` SPL tokens from
+    /// the authority's token account
+    ///
+    /// The token program rejects the burn, and with it the decrement, if the
+    /// balance is insufficient.
+    pub fn decrement_with_burn(ctx: Context<DecrementWithBurn>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        lock_counter(&mut ctx.accounts.counter)?;
+        burn_tokens(
+            &ctx.accounts.token_program,
+            &ctx.accounts.token_account,
+            &ctx.accounts.mint,
+            &ctx.accounts.authority,
+            amount,
+        )?;
+
+        let counter = &mut ctx.accounts.counter;
+        counter.locked = false;
+        counter.apply_decrement(1, Clock::get()?.unix_timestamp)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Burned {} tokens, counter decremented to: {}", amount, counter.count);
+        emit!(CounterDecremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Apply a list of signed deltas in order within one instruction
+    ///
+    /// Overflow, underflow and bounds are checked after every step, so any
+    /// violation aborts the whole list. The rate limit is checked once.
+    pub fn apply_deltas(ctx: Context<Decrement>, deltas: Vec<i64>) -> Result<()> {
+        require!(deltas.len() <= MAX_DELTAS, ErrorCode::TooManyDeltas);
+
+        let counter = &mut ctx.accounts.counter;
+        let now = Clock::get()?.unix_timestamp;
+        counter.check_rate_limit(now)?;
+        let previous = counter.count;
+
+        for delta in deltas.iter() {
+            require!(*delta != 0, ErrorCode::InvalidAmount);
+            if *delta > 0 {
+                record_increment(&mut ctx.accounts.config);
+                counter.apply_increment_unthrottled(delta.unsigned_abs(), now)?;
+                emit_reached_milestones(counter);
+            } else {
+                counter.apply_decrement(delta.unsigned_abs(), now)?;
+            }
+        }
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Applied {} deltas, counter now: {}", deltas.len(), counter.count);
+        emit!(DeltasApplied {
+            counter: counter.key(),
+            authority: counter.authority,
+            previous_count: previous,
+            count: counter.count,
+            deltas_applied: deltas.len() as u32,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Queue an increment for `crank` to apply once `at` has passed
+    pub fn schedule_increment(ctx: Context<ScheduleIncrement>, at: i64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        require!(counter.scheduled.len() < MAX_SCHEDULED, ErrorCode::QueueFull);
+        counter.scheduled.push(at);
+
+        msg!("Increment scheduled at {}", at);
+        Ok(())
+    }
+
+    /// Apply every queued increment that is due and drop it from the queue,
+    /// returning how many were applied; anyone may crank
+    ///
+    /// As with `apply_deltas`, the rate limit is checked once for the batch.
+    pub fn crank(ctx: Context<Crank>) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        let now = Clock::get()?.unix_timestamp;
+        let due = counter.scheduled.iter().filter(|at| **at <= now).count();
+        if due == 0 {
+            msg!("No scheduled increments due");
+            return Ok(0);
+        }
+
+        counter.check_rate_limit(now)?;
+        for _ in 0..due {
+            record_increment(&mut ctx.accounts.config);
+            counter.apply_increment_unthrottled(1, now)?;
+            emit_reached_milestones(counter);
+        }
+        counter.scheduled.retain(|at| *at > now);
+        counter.last_modifier = ctx.accounts.cranker.key();
+
+        msg!("Cranked {} scheduled increments, counter now: {}", due, counter.count);
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: counter.authority,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(due as u64)
+    }
+
+    /// Reset the counter back to its floor, zero unless `min_value` is set
+    pub fn reset(ctx: Context<Reset>) -> Result<()> {
+        let min_value = ctx.accounts.counter.min_value;
+        reset_to(ctx, min_value)
+    }
+
+    /// Reset every counter passed in `remaining_accounts` to its floor
+    ///
+    /// Each account must be a writable counter owned by this program whose
+    /// authority is the signer; any mismatch aborts the whole batch.
+    pub fn batch_reset<'info>(ctx: Context<'_, '_, 'info, 'info, BatchReset<'info>>) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH,
+            ErrorCode::BatchTooLarge
+        );
+        let authority = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        for account_info in ctx.remaining_accounts.iter() {
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+            // Verifies program ownership and the account discriminator
+            let mut counter = Account::<Counter>::try_from(account_info)?;
+            require_keys_eq!(counter.authority, authority, ErrorCode::Unauthorized);
+            counter.assert_mutable()?;
+            let previous = counter.count;
+
+            let floor = counter.min_value;
+            counter.write_count(floor, now)?;
+            counter.last_modifier = authority;
+            counter.exit(&crate::ID)?;
+
+            emit!(CounterReset {
+                counter: counter.key(),
+                authority,
+                previous_count: previous,
+                count: counter.count,
+                timestamp: now,
+            });
+        }
+
+        msg!("Batch reset {} counters", ctx.remaining_accounts.len());
+        Ok(())
+    }
+
+    /// Reset the counter to an arbitrary starting value within its bounds
+    pub fn reset_to(ctx: Context<Reset>, value: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        counter.check_max(value)?;
+        counter.check_min(value)?;
+        let previous = counter.count;
+
+        let now = Clock::get()?.unix_timestamp;
+        counter.write_count(value, now)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter reset to {} (previous value: {})", value, previous);
+        emit!(CounterReset {
+            counter: counter.key(),
+            authority: counter.authority,
+            previous_count: previous,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Jump the counter to an exact value within its configured bounds
+    ///
+    /// With `max_change_bps` set, the move must also stay within that share
+    /// of the current value.
+    pub fn set_count(ctx: Context<Reset>, value: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        counter.apply_decay(now);
+        counter.check_max(value)?;
+        counter.check_min(value)?;
+        counter.check_change(value)?;
+        let previous = counter.count;
+
+        counter.write_count(value, now)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+        emit_reached_milestones(counter);
+
+        msg!("Counter set to {} (previous value: {})", value, previous);
+        emit!(CounterReset {
+            counter: counter.key(),
+            authority: counter.authority,
+            previous_count: previous,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Drop the counter to its floor and return how much was drained above
+    /// it, after decay
+    pub fn drain(ctx: Context<Reset>) -> Result<u64> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        counter.apply_decay(now);
+        let previous = counter.count;
+        let drained = previous.saturating_sub(counter.min_value);
+
+        let floor = counter.min_value;
+        counter.write_count(floor, now)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Counter drained: {}", drained);
+        emit!(CounterReset {
+            counter: counter.key(),
+            authority: counter.authority,
+            previous_count: previous,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(drained)
+    }
+
+    /// Exchange the values of two counters owned by the same authority
+    ///
+    /// Each value must fit the bounds of the counter it moves into.
+    pub fn swap(ctx: Context<Swap>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let counter_a = &mut ctx.accounts.counter_a;
+        let counter_b = &mut ctx.accounts.counter_b;
+        counter_a.assert_mutable()?;
+        counter_b.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        counter_a.apply_decay(now);
+        counter_b.apply_decay(now);
+
+        let (count_a, count_b) = (counter_a.count, counter_b.count);
+        counter_a.check_max(count_b)?;
+        counter_a.check_min(count_b)?;
+        counter_b.check_max(count_a)?;
+        counter_b.check_min(count_a)?;
+
+        for (counter, value) in [(&mut *counter_a, count_b), (&mut *counter_b, count_a)] {
+            counter.write_count(value, now)?;
+            counter.last_modifier = authority;
+        }
+
+        msg!("Swapped counter values {} and {}", count_a, count_b);
+        emit!(CountersSwapped {
+            counter_a: counter_a.key(),
+            counter_b: counter_b.key(),
+            count_a: counter_a.count,
+            count_b: counter_b.count,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Save the current value so it can be restored later
+    pub fn save_checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        counter.apply_decay(now);
+        counter.checkpoint = counter.count;
+        counter.accrue(now);
+        counter.last_updated = now;
+        counter.last_slot = Clock::get()?.slot;
+
+        msg!("Checkpoint saved at value: {}", counter.checkpoint);
+        Ok(())
+    }
+
+    /// Roll the counter back to the saved checkpoint
+    ///
+    /// The bounds are checked again, since they may have been tightened
+    /// after the checkpoint was saved.
+    pub fn restore_checkpoint(ctx: Context<Checkpoint>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let checkpoint = counter.checkpoint;
+        counter.check_max(checkpoint)?;
+        counter.check_min(checkpoint)?;
+        let previous = counter.count;
+
+        let now = Clock::get()?.unix_timestamp;
+        counter.write_count(checkpoint, now)?;
+        counter.last_modifier = ctx.accounts.authority.key();
+
+        msg!("Checkpoint restored to {} (previous value: {})", counter.count, previous);
+        emit!(CounterReset {
+            counter: counter.key(),
+            authority: counter.authority,
+            previous_count: previous,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+        Ok(())
+    }
+
+    /// Close the counter and refund its rent to the authority
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        let counter_key = ctx.accounts.counter.key();
+        if let Some(registry) = ctx.accounts.registry.as_deref_mut() {
+            registry.counters.retain(|key| *key != counter_key);
+        }
+        let config = &mut ctx.accounts.config;
+        config.total_closed = config.total_closed.saturating_add(1);
+
+        msg!("Counter closed: {}", ctx.accounts.counter.key());
+        emit!(CounterClosed {
+            counter: ctx.accounts.counter.key(),
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Add the source counter's count into the destination and close the
+    /// source, refunding its rent to the authority
+    pub fn merge(ctx: Context<Merge>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let source = &ctx.accounts.source;
+        source.assert_mutable()?;
+        let (source_key, amount) = (source.key(), source.effective_count(now));
+
+        let destination = &mut ctx.accounts.destination;
+        destination.assert_mutable()?;
+        destination.apply_decay(now);
+        let value = destination.count
+            .checked_add(amount)
+            .ok_or(ErrorCode::AddOverflow)?;
+        destination.check_max(value)?;
+
+        destination.write_count(value, now)?;
+        destination.last_modifier = ctx.accounts.authority.key();
+
+        if let Some(registry) = ctx.accounts.registry.as_deref_mut() {
+            registry.counters.retain(|key| *key != source_key);
+        }
+        let config = &mut ctx.accounts.config;
+        config.total_closed = config.total_closed.saturating_add(1);
+
+        msg!("Merged {} from {} into {}, count: {}", amount, source_key, destination.key(), value);
+        emit!(CounterClosed {
+            counter: source_key,
+            authority: ctx.accounts.authority.key(),
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Make `read_and_count` increment the counter on every read
+    pub fn set_count_on_read(ctx: Context<SetCountOnRead>, count_on_read: bool) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.count_on_read = count_on_read;
+
+        msg!("Counter count on read: {}", count_on_read);
+        Ok(())
+    }
+
+    /// Push the timer's end out by `seconds`, starting it from now if it has
+    /// never been set or has already run out
+    pub fn extend(ctx: Context<Extend>, seconds: i64) -> Result<()> {
+        require!(seconds > 0, ErrorCode::InvalidAmount);
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+
+        let now = Clock::get()?.unix_timestamp;
+        counter.end_ts = counter.end_ts
+            .max(now)
+            .checked_add(seconds)
+            .ok_or(ErrorCode::AddOverflow)?;
+
+        msg!("Timer extended by {} seconds, ends at {}", seconds, counter.end_ts);
+        Ok(())
+    }
+
+    /// Reset a zero or future `last_updated` to the current time
+    ///
+    /// The accumulator is not accrued over the bad interval, since it would
+    /// otherwise credit the count for every second since the epoch.
+    pub fn repair_timestamp(ctx: Context<RepairTimestamp>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        let previous = counter.last_updated;
+        require!(previous == 0 || previous > now, ErrorCode::NothingToRepair);
+        counter.last_updated = now;
+        counter.last_slot = Clock::get()?.slot;
+
+        msg!("Repaired last_updated from {} to {}", previous, now);
+        Ok(())
+    }
+
+    /// Let anyone close the counter once it has been idle for longer than
+    /// `expires_after` seconds; zero disables expiry
+    pub fn set_expires_after(ctx: Context<SetExpiresAfter>, expires_after: i64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.expires_after = expires_after;
+
+        msg!("Counter expires after {} idle seconds", expires_after);
+        Ok(())
+    }
+
+    /// Close an expired counter on behalf of any signer, refunding its rent
+    /// to the stored authority
+    pub fn close_expired(ctx: Context<CloseExpired>) -> Result<()> {
+        let counter = &ctx.accounts.counter;
+        let idle = Clock::get()?.unix_timestamp.saturating_sub(counter.last_updated);
+        require!(
+            counter.expires_after > 0 && idle > counter.expires_after,
+            ErrorCode::NotExpired
+        );
+
+        let counter_key = counter.key();
+        if let Some(registry) = ctx.accounts.registry.as_deref_mut() {
+            registry.counters.retain(|key| *key != counter_key);
+        }
+        let config = &mut ctx.accounts.config;
+        config.total_closed = config.total_closed.saturating_add(1);
+
+        msg!("Expired counter closed: {}", counter_key);
+        emit!(CounterClosed {
+            counter: counter_key,
+            authority: ctx.accounts.authority.key(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Return lamports sent to the counter beyond its rent-exempt minimum to
+    /// the authority, and return the amount swept
+    ///
+    /// `None` sweeps all of the excess; a larger `amount` than that fails
+    /// with `WouldBeNonRentExempt`.
+    pub fn sweep_excess(ctx: Context<SweepExcess>, amount: Option<u64>) -> Result<u64> {
+        let counter = ctx.accounts.counter.to_account_info();
+        let rent = Rent::get()?.minimum_balance(counter.data_len());
+        let amount = amount.unwrap_or_else(|| counter.lamports().saturating_sub(rent));
+        require!(
+            amount <= counter.lamports(),
+            ErrorCode::WouldBeNonRentExempt
+        );
+
+        if amount > 0 {
+            counter.sub_lamports(amount)?;
+            ctx.accounts.authority.add_lamports(amount)?;
+        }
+        assert_rent_exempt(&counter)?;
+
+        msg!("Swept {} excess lamports", amount);
+        Ok(amount)
+    }
+
+    /// Close the authority's counter PDA, keeping only a hash of its state
+    ///
+    /// The full serialized state is emitted in `CounterCompressed`; clients
+    /// must keep it (or find it in transaction history) to `decompress`.
+    pub fn compress(ctx: Context<Compress>) -> Result<()> {
+        let counter = &ctx.accounts.counter;
+        let state = counter.try_to_vec()?;
+        let counter_key = counter.key();
+
+        let compressed = &mut ctx.accounts.compressed;
+        compressed.authority = ctx.accounts.authority.key();
+        compressed.state_hash = hash(&state).to_bytes();
+        compressed.space = counter.to_account_info().data_len() as u64;
+        compressed.bump = ctx.bumps.compressed;
+
+        if let Some(registry) = ctx.accounts.registry.as_deref_mut() {
+            registry.counters.retain(|key| *key != counter_key);
+        }
+
+        msg!("Counter compressed: {}", counter_key);
+        emit!(CounterCompressed {
+            counter: counter_key,
+            authority: ctx.accounts.authority.key(),
+            state,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Recreate a compressed counter from the state emitted by `compress`
+    ///
+    /// `state` must hash to the stored value, so it is restored exactly.
+    pub fn decompress(ctx: Context<Decompress>, state: Vec<u8>) -> Result<()> {
+        require!(
+            hash(&state).to_bytes() == ctx.accounts.compressed.state_hash,
+            ErrorCode::CompressedStateMismatch
+        );
+        let restored = Counter::try_from_slice(&state)?;
+        ctx.accounts.counter.set_inner(restored);
+
+        msg!("Counter decompressed: {}", ctx.accounts.counter.key());
+        register_counter(
+            &mut ctx.accounts.registry,
+            ctx.accounts.authority.key(),
+            ctx.accounts.counter.key(),
+            ctx.bumps.registry,
+        )
+    }
+
+    /// Move the authority's default counter to the PDA
+    /// `[b"counter", new_seed_suffix, authority]`, copying its state and
+    /// closing the original
+    ///
+    /// Its metadata moves too when `old_meta` and `new_meta` are passed. The
+    /// escrow vault must be empty. The bounty vault is seeded by the counter
+    /// address too and stays behind, so it should be emptied first.
+    pub fn migrate_seed(ctx: Context<MigrateSeed>, new_seed_suffix: String) -> Result<()> {
+        let state = (*ctx.accounts.old_counter).clone();
+        ctx.accounts.new_counter.set_inner(state);
+
+        let (old_key, new_key) = (ctx.accounts.old_counter.key(), ctx.accounts.new_counter.key());
+        if let Some(old_meta) = &ctx.accounts.old_meta {
+            let (Some(new_meta), Some(bump)) = (&ctx.accounts.new_meta, ctx.bumps.new_meta) else {
+                return err!(ErrorCode::MissingMeta);
+            };
+            create_meta(
+                &new_meta.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                new_key,
+                bump,
+                (**old_meta).clone(),
+            )?;
+        }
+        if let Some(registry) = ctx.accounts.registry.as_deref_mut() {
+            for key in registry.counters.iter_mut() {
+                if *key == old_key {
+                    *key = new_key;
+                }
+            }
+        }
+
+        msg!("Counter moved from {} to {} (suffix {:?})", old_key, new_key, new_seed_suffix);
+        Ok(())
+    }
+
+    /// Bring a counter created with an older layout up to the current size
+    /// and version
+    ///
+    /// Version 1 and older counters held their label, note, tags and unit
+    /// inline; those move to the counter's `CounterMeta`, which is created
+    /// here if it does not exist yet and left alone if it does. The account
+    /// is then resized to `Counter::LEN`: the authority tops up rent when it
+    /// grows and gets the freed rent back when it shrinks. New fields are
+    /// zero-filled, and calling this on an up-to-date counter is a no-op.
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        let counter = ctx.accounts.counter.to_account_info();
+        {
+            let data = counter.try_borrow_data()?;
+            require!(
+                data.starts_with(&Counter::DISCRIMINATOR),
+                anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+            );
+            require!(
+                data.len() >= 40 && data[8..40] == ctx.accounts.authority.key().to_bytes(),
+                ErrorCode::Unauthorized
+            );
+        }
+
+        // Fields missing from an older layout read as zero, exactly as they
+        // will once the account is resized
+        let old_len = counter.data_len();
+        let mut padded = counter.try_borrow_data()?.to_vec();
+        padded.resize(old_len + Counter::LEN, 0);
+        let up_to_date = old_len == Counter::LEN
+            && Counter::try_deserialize(&mut &padded[..])
+                .is_ok_and(|current| current.version >= COUNTER_VERSION);
+        if up_to_date {
+            msg!("Counter already at version {}", COUNTER_VERSION);
+            return Ok(());
+        }
+
+        let (data, legacy) = split_legacy_counter(&padded)?;
+        let mut migrated = Counter::try_deserialize(&mut &data[..])?;
+        migrated.version = COUNTER_VERSION;
+
+        if ctx.accounts.meta.data_is_empty() {
+            create_meta(
+                &ctx.accounts.meta.to_account_info(),
+                &ctx.accounts.authority.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                counter.key(),
+                ctx.bumps.meta,
+                legacy,
+            )?;
+        }
+
+        let rent = Rent::get()?;
+        let rent_needed = rent.minimum_balance(Counter::LEN);
+        if old_len < Counter::LEN {
+            let rent_due = rent_needed.saturating_sub(counter.lamports());
+            if rent_due > 0 {
+                system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.authority.to_account_info(),
+                            to: counter.clone(),
+                        },
+                    ),
+                    rent_due,
+                )?;
+            }
+        }
+        counter.realloc(Counter::LEN, true)?;
+        // Only the rent freed by shrinking goes back, not any other excess
+        let refund = rent
+            .minimum_balance(old_len)
+            .saturating_sub(rent_needed)
+            .min(counter.lamports().saturating_sub(rent_needed));
+        if refund > 0 {
+            counter.sub_lamports(refund)?;
+            ctx.accounts.authority.add_lamports(refund)?;
+        }
+        assert_rent_exempt(&counter)?;
+
+        let mut data = counter.try_borrow_mut_data()?;
+        migrated.try_serialize(&mut &mut data[..])?;
+
+        msg!(
+            "Counter migrated: {} -> {} bytes, version {}",
+            old_len,
+            data.len(),
+            migrated.version
+        );
+        Ok(())
+    }
+
+    /// Transfer counter authority (ownership)
+    ///
+    /// When a threshold is configured, additional co-owners approve by
+    /// signing and being passed in `remaining_accounts`.
+    pub fn transfer_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, TransferAuthority<'info>>, 
+        new_authority: Pubkey
+    ) -> Result<()> {
+        // The default key has no signer, so a transfer there bricks the counter
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+        require!(
+            new_authority != ctx.accounts.counter.authority,
+            ErrorCode::SameAuthority
+        );
+
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        counter.check_threshold(
+            ctx.accounts.authority.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        let previous_authority = counter.transfer_to(new_authority)?;
+
+        msg!("Authority transferred to: {}", new_authority);
+        emit!(AuthorityTransferred {
+            counter: counter.key(),
+            previous_authority,
+            new_authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Move every counter passed in `remaining_accounts` to `new_authority`
+    ///
+    /// Each account must be a writable counter owned by the signer; any
+    /// failure aborts the whole batch. Counters with a co-owner threshold
+    /// must use `transfer_authority` so the co-owners can sign.
+    pub fn batch_transfer_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BatchTransferAuthority<'info>>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_BATCH,
+            ErrorCode::BatchTooLarge
+        );
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+
+        let authority = ctx.accounts.authority.key();
+        require!(new_authority != authority, ErrorCode::SameAuthority);
+        let now = Clock::get()?.unix_timestamp;
+
+        for account_info in ctx.remaining_accounts.iter() {
+            require!(account_info.is_writable, ErrorCode::AccountNotWritable);
+
+            // Verifies program ownership and the account discriminator
+            let mut counter = Account::<Counter>::try_from(account_info)?;
+            require_keys_eq!(counter.authority, authority, ErrorCode::Unauthorized);
+            counter.assert_mutable()?;
+            counter.check_threshold(ctx.accounts.authority.as_ref(), &[])?;
+            counter.transfer_to(new_authority)?;
+            counter.exit(&crate::ID)?;
+
+            emit!(AuthorityTransferred {
+                counter: counter.key(),
+                previous_authority: authority,
+                new_authority,
+                timestamp: now,
+            });
+        }
+
+        msg!(
+            "Transferred {} counters to: {}",
+            ctx.remaining_accounts.len(),
+            new_authority
+        );
+        Ok(())
+    }
+
+    /// Increment the counter and hand it to `new_authority` atomically
+    ///
+    /// Runs the same guards as `increment` and `transfer_authority`;
+    /// co-owner approvals go in `remaining_accounts` as for a transfer.
+    pub fn increment_and_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, IncrementAndTransfer<'info>>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        require!(new_authority != Pubkey::default(), ErrorCode::InvalidAuthority);
+        require!(
+            new_authority != ctx.accounts.counter.authority,
+            ErrorCode::SameAuthority
+        );
+
+        let counter = &mut ctx.accounts.counter;
+        let now = Clock::get()?.unix_timestamp;
+        record_increment(&mut ctx.accounts.config);
+        counter.apply_increment(1, now)?;
+        emit_reached_milestones(counter);
+        counter.last_modifier = ctx.accounts.authority.key();
+        counter.check_threshold(
+            ctx.accounts.authority.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        let previous_authority = counter.transfer_to(new_authority)?;
+
+        msg!(
+            "Counter incremented to {} and transferred to: {}",
+            counter.count,
+            new_authority
+        );
+        emit!(CounterIncremented {
+            counter: counter.key(),
+            authority: previous_authority,
+            count: counter.count,
+            timestamp: now,
+        });
+        emit!(AuthorityTransferred {
+            counter: counter.key(),
+            previous_authority,
+            new_authority,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Configure co-owners and how many of them must approve a transfer
+    ///
+    /// A `threshold` of zero disables the requirement. Once enabled, changing
+    /// the co-owner set needs the current threshold of approvals as well.
+    pub fn set_authorities<'info>(
+        ctx: Context<'_, '_, '_, 'info, SetAuthorities<'info>>,
+        authorities: [Pubkey; MAX_AUTHORITIES],
+        threshold: u8,
+    ) -> Result<()> {
+        let members = authorities
+            .iter()
+            .enumerate()
+            .filter(|(i, key)| {
+                **key != Pubkey::default() && !authorities[..*i].contains(key)
+            })
+            .count();
+        require!(threshold as usize <= members, ErrorCode::InvalidThreshold);
+
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        counter.check_threshold(
+            ctx.accounts.authority.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        counter.authorities = authorities;
+        counter.threshold = threshold;
+
+        msg!("Co-owners updated, threshold: {}", threshold);
+        Ok(())
+    }
+
+    /// Propose a new authority; it only takes effect once accepted
+    ///
+    /// Subject to the same co-owner threshold as `transfer_authority`.
+    pub fn propose_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, ProposeAuthority<'info>>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        counter.check_threshold(
+            ctx.accounts.authority.as_ref(),
+            ctx.remaining_accounts,
+        )?;
+        counter.pending_authority = new_authority;
+        let now = Clock::get()?.unix_timestamp;
+        counter.transfer_effective_at = now.saturating_add(counter.transfer_timelock);
+
+        msg!("Authority transfer proposed to: {}", new_authority);
+        emit!(AuthorityProposed {
+            counter: counter.key(),
+            authority: counter.authority,
+            pending_authority: new_authority,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Accept a proposed authority transfer (signed by the pending authority)
+    ///
+    /// Fails until `transfer_timelock` seconds have passed since the proposal,
+    /// giving the current authority a window to cancel it.
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        let now = Clock::get()?.unix_timestamp;
+        if now < counter.transfer_effective_at {
+            msg!("Transfer can be accepted at {}, now {}", counter.transfer_effective_at, now);
+            return err!(ErrorCode::TimelockActive);
+        }
+        let pending_authority = counter.pending_authority;
+        let previous_authority = counter.transfer_to(pending_authority)?;
+
+        msg!("Authority transfer accepted by: {}", counter.authority);
+        emit!(AuthorityTransferred {
+            counter: counter.key(),
+            previous_authority,
+            new_authority: counter.authority,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Abort a pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<ProposeAuthority>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        require!(
+            counter.pending_authority != Pubkey::default(),
+            ErrorCode::NoPendingAuthority
+        );
+        counter.pending_authority = Pubkey::default();
+        counter.transfer_effective_at = 0;
+
+        msg!("Authority transfer cancelled");
+        Ok(())
+    }
+
+    /// Require proposed authorities to wait `seconds` before accepting;
+    /// applies to proposals made after this call
+    pub fn set_transfer_timelock(ctx: Context<SetTransferTimelock>, seconds: i64) -> Result<()> {
+        require!(seconds >= 0, ErrorCode::InvalidAmount);
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.transfer_timelock = seconds;
+
+        msg!("Authority transfer timelock set to {} seconds", seconds);
+        Ok(())
+    }
+
+    /// Rename the counter in its metadata account
+    pub fn set_label(ctx: Context<UpdateMeta>, label: String) -> Result<()> {
+        require!(label.len() <= MAX_LABEL_LEN, ErrorCode::LabelTooLong);
+        ctx.accounts.counter.assert_mutable()?;
+        ctx.accounts.meta.label = label;
+
+        msg!("Counter label set to: {}", ctx.accounts.meta.label);
+        Ok(())
+    }
+
+    /// Update several settings in one call; only `Some` fields are applied
+    ///
+    /// Everything is validated before anything changes, so either all of
+    /// the requested updates land or none do. A new label is written to the
+    /// metadata account, which must then be passed.
+    pub fn configure(ctx: Context<Configure>, params: ConfigParams) -> Result<()> {
+        if let Some(label) = &params.label {
+            require!(label.len() <= MAX_LABEL_LEN, ErrorCode::LabelTooLong);
+            require!(ctx.accounts.meta.is_some(), ErrorCode::MissingMeta);
+        }
+
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_mutable()?;
+        if let Some(max_value) = params.max_value {
+            counter.max_value = max_value;
+            counter.check_max(counter.count)?;
+        }
+        if let Some(min_interval_seconds) = params.min_interval_seconds {
+            counter.min_interval_seconds = min_interval_seconds;
+        }
+        if let (Some(label), Some(meta)) = (params.label, ctx.accounts.meta.as_deref_mut()) {
+            meta.label = label;
+        }
+
+        msg!(
+            "Counter configured: max_value {}, min_interval {}",
+            counter.max_value,
+            counter.min_interval_seconds
+        );
+        Ok(())
+    }
+
+    /// Replace the freeform note in the counter's metadata account; an
+    /// empty string clears it
+    pub fn set_note(ctx: Context<UpdateMeta>, note: String) -> Result<()> {
+        require!(note.len() <= MAX_NOTE_LEN, ErrorCode::NoteTooLong);
+        ctx.accounts.counter.assert_mutable()?;
+        ctx.accounts.meta.note = note;
+
+        msg!("Counter note updated ({} bytes)", ctx.accounts.meta.note.len());
+        Ok(())
+    }
+
+    /// Restrict `get_count` to the authority and its readers, or lift the
+    /// restriction
+    pub fn set_private(ctx: Context<ManageReaders>, private: bool) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.private = private;
+
+        msg!("Counter private: {}", private);
+        Ok(())
+    }
+
+    /// Let `reader` call `get_count` on a private counter
+    pub fn grant_reader(ctx: Context<ManageReaders>, reader: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        let readers = &mut counter.readers;
+        if !readers.contains(&reader) {
+            require!(readers.len() < MAX_READERS, ErrorCode::ReadersFull);
+            readers.push(reader);
+        }
+
+        msg!("Reader granted: {}", reader);
+        Ok(())
+    }
+
+    /// Remove `reader` from the counter's readers
+    pub fn revoke_reader(ctx: Context<ManageReaders>, reader: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.readers.retain(|key| key != &reader);
+
+        msg!("Reader revoked: {}", reader);
+        Ok(())
+    }
+
+    /// Set the second signer required on increments; the default pubkey
+    /// removes the requirement
+    pub fn set_cosigner(ctx: Context<SetCosigner>, cosigner: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.cosigner = cosigner;
+
+        msg!("Cosigner set to: {}", cosigner);
+        Ok(())
+    }
+
+    /// Replace the milestones `increment` reports on; zero slots are unused
+    ///
+    /// Every milestone may fire again, including ones already passed, which
+    /// fire on the next increment.
+    pub fn set_milestones(ctx: Context<SetMilestones>, milestones: [u64; 4]) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.milestones = milestones;
+        counter.milestones_fired = 0;
+
+        msg!("Milestones set to: {:?}", milestones);
+        Ok(())
+    }
+
+    /// Rate limit increments with a token bucket holding up to `capacity`
+    /// tokens; the bucket starts full and a zero capacity removes it
+    pub fn set_token_bucket(
+        ctx: Context<SetTokenBucket>,
+        capacity: u64,
+        refill_per_second: u64,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.bucket_capacity = capacity;
+        counter.refill_per_second = refill_per_second;
+        counter.tokens = capacity;
+        counter.last_refill = Clock::get()?.unix_timestamp;
+
+        msg!("Token bucket set: capacity {}, refill {}/s", capacity, refill_per_second);
+        Ok(())
+    }
+
+    /// Allow at most `daily_cap` increments per UTC day; zero removes the cap
+    pub fn set_daily_cap(ctx: Context<SetDailyCap>, daily_cap: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.daily_cap = daily_cap;
+
+        msg!("Daily cap set to {}", daily_cap);
+        Ok(())
+    }
+
+    /// Trip a breaker that blocks increments for `cooldown_seconds` once
+    /// `max_per_window` land within `window_seconds`; zero turns it off
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreaker>,
+        max_per_window: u32,
+        window_seconds: i64,
+        cooldown_seconds: i64,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.max_per_window = max_per_window;
+        counter.window_seconds = window_seconds;
+        counter.cooldown_seconds = cooldown_seconds;
+        counter.increments_in_window = 0;
+        counter.window_start = Clock::get()?.unix_timestamp;
+        counter.breaker_until = 0;
+
+        msg!(
+            "Circuit breaker set: {} per {}s, {}s cooldown",
+            max_per_window,
+            window_seconds,
+            cooldown_seconds
+        );
+        Ok(())
+    }
+
+    /// Attach a tag in the counter's metadata account
+    pub fn add_tag(ctx: Context<AddTag>, tag: String) -> Result<()> {
+        require!(tag.len() <= MAX_TAG_LEN, ErrorCode::TagTooLong);
+        ctx.accounts.counter.assert_mutable()?;
+
+        let tags = &mut ctx.accounts.meta.tags;
+        require!(tags.len() < MAX_TAGS, ErrorCode::TooManyTags);
+        require!(!tags.contains(&tag), ErrorCode::DuplicateTag);
+        tags.push(tag);
+
+        msg!("Counter now has {} tags", tags.len());
+        Ok(())
+    }
+
+    /// Remove a tag from the counter's metadata account
+    pub fn remove_tag(ctx: Context<RemoveTag>, tag: String) -> Result<()> {
+        ctx.accounts.counter.assert_mutable()?;
+
+        let tags = &mut ctx.accounts.meta.tags;
+        let position = tags
+            .iter()
+            .position(|existing| existing == &tag)
+            .ok_or(ErrorCode::TagNotFound)?;
+        tags.remove(position);
+
+        msg!("Counter now has {} tags", tags.len());
+        Ok(())
+    }
+
+    /// Create the counter's empty metadata account
+    ///
+    /// Label, note, tags and unit are edited through the metadata setters,
+    /// which write only the meta account and leave the counter's data
+    /// untouched. Counters from before the split get theirs from `migrate`.
+    pub fn initialize_meta(ctx: Context<InitializeMeta>) -> Result<()> {
+        let meta = &mut ctx.accounts.meta;
+        meta.counter = ctx.accounts.counter.key();
+        meta.bump = ctx.bumps.meta;
+
+        msg!("Metadata created for counter {}", meta.counter);
+        Ok(())
+    }
+
+    /// Replace the whole tag list in the counter's metadata account,
+    /// resizing it to fit the new list
+    pub fn set_meta_tags(ctx: Context<SetMetaTags>, tags: Vec<String>) -> Result<()> {
+        require!(tags.len() <= MAX_TAGS, ErrorCode::TooManyTags);
+        for (i, tag) in tags.iter().enumerate() {
+            require!(tag.len() <= MAX_TAG_LEN, ErrorCode::TagTooLong);
+            require!(!tags[..i].contains(tag), ErrorCode::DuplicateTag);
+        }
+        ctx.accounts.counter.assert_mutable()?;
+        ctx.accounts.meta.tags = tags;
+
+        msg!("Meta now has {} tags", ctx.accounts.meta.tags.len());
+        Ok(())
+    }
+
+    /// Set the unit recorded in the counter's metadata account
+    pub fn set_meta_unit(ctx: Context<UpdateMeta>, unit: Unit) -> Result<()> {
+        ctx.accounts.counter.assert_mutable()?;
+        ctx.accounts.meta.unit = unit;
+
+        msg!("Meta unit set to: {:?}", unit);
+        Ok(())
+    }
+
+    /// Set the lamports paid from the bounty vault on each increment
+    pub fn set_bounty(ctx: Context<SetBounty>, per_increment: u64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.bounty_lamports = per_increment;
+
+        msg!("Bounty set to {} lamports per increment", per_increment);
+        Ok(())
+    }
+
+    /// Deposit lamports into the counter's bounty vault; anyone may fund it
+    pub fn fund_bounty(ctx: Context<FundBounty>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.bounty_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        msg!("Bounty vault funded with {} lamports", amount);
+        Ok(())
+    }
+
+    /// Set the count the escrow vault unlocks at
+    ///
+    /// The target is fixed once the vault holds funds, so backers know the
+    /// goal they are funding cannot be lowered afterwards.
+    pub fn set_escrow_target(ctx: Context<SetEscrowTarget>, target: u64) -> Result<()> {
+        require!(ctx.accounts.escrow_vault.lamports() == 0, ErrorCode::EscrowFunded);
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.target = target;
+
+        msg!("Escrow target set to {}", target);
+        Ok(())
+    }
+
+    /// Deposit lamports into the counter's escrow vault; anyone may fund it
+    pub fn fund_escrow(ctx: Context<FundEscrow>, amount: u64) -> Result<()> {
+        require!(amount > 0, ErrorCode::InvalidAmount);
+        require!(ctx.accounts.counter.target > 0, ErrorCode::EscrowTargetNotSet);
+
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.funder.to_account_info(),
+                    to: ctx.accounts.escrow_vault.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+
+        msg!("Escrow vault funded with {} lamports", amount);
+        Ok(())
+    }
+
+    /// Release the whole escrow vault to the authority once the count has
+    /// reached the target
+    pub fn claim_escrow(ctx: Context<ClaimEscrow>) -> Result<()> {
+        let counter = &ctx.accounts.counter;
+        require!(
+            counter.target > 0 && counter.count >= counter.target,
+            ErrorCode::TargetNotReached
+        );
+
+        let amount = ctx.accounts.escrow_vault.lamports();
+        let counter_key = counter.key();
+        system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.escrow_vault.to_account_info(),
+                    to: ctx.accounts.authority.to_account_info(),
+                },
+                &[&[b"escrow", counter_key.as_ref(), &[ctx.bumps.escrow_vault]]],
+            ),
+            amount,
+        )?;
+
+        msg!("Claimed {} escrowed lamports", amount);
+        Ok(())
+    }
+
+    /// Allow another key to increment the counter
+    pub fn set_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.delegate = delegate;
+
+        msg!("Delegate set to: {}", delegate);
+        Ok(())
+    }
+
+    /// Remove the delegate
+    pub fn revoke_delegate(ctx: Context<SetDelegate>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.delegate = Pubkey::default();
+
+        msg!("Delegate revoked");
+        Ok(())
+    }
+
+    /// Add `delegate` to the list of keys allowed to increment, alongside the
+    /// single `delegate` slot
+    pub fn add_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        if !counter.delegates.contains(&delegate) {
+            require!(
+                counter.delegates.len() < MAX_DELEGATES,
+                ErrorCode::DelegateLimitReached
+            );
+            counter.delegates.push(delegate);
+        }
+
+        msg!("Delegate added: {}", delegate);
+        Ok(())
+    }
+
+    /// Remove `delegate` from the list of keys allowed to increment
+    pub fn remove_delegate(ctx: Context<SetDelegate>, delegate: Pubkey) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.delegates.retain(|key| key != &delegate);
+
+        msg!("Delegate removed: {}", delegate);
+        Ok(())
+    }
+
+    /// Hand the pause/unpause role to another key (authority only)
+    pub fn set_freeze_authority(
+        ctx: Context<SetFreezeAuthority>,
+        freeze_authority: Pubkey,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.freeze_authority = freeze_authority;
+
+        msg!("Freeze authority set to: {}", freeze_authority);
+        Ok(())
+    }
+
+    /// Pause the counter, blocking all mutations until unpaused
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.is_paused = true;
+
+        msg!("Counter paused");
+        Ok(())
+    }
+
+    /// Unpause the counter
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.is_paused = false;
+        counter.paused_until = 0;
+
+        msg!("Counter unpaused");
+        Ok(())
+    }
+
+    /// Pause the counter until `timestamp`, after which mutations resume
+    /// without a manual unpause
+    pub fn pause_until(ctx: Context<SetPaused>, timestamp: i64) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.assert_not_archived()?;
+        counter.paused_until = timestamp;
+
+        msg!("Counter paused until: {}", timestamp);
+        Ok(())
+    }
+
+    /// Archive the counter: it keeps its value and stays readable, but every
+    /// mutation fails until it is unarchived
+    pub fn archive(ctx: Context<SetArchived>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.archived = true;
+
+        msg!("Counter archived at value: {}", counter.count);
+        Ok(())
+    }
+
+    /// Make an archived counter writable again
+    pub fn unarchive(ctx: Context<SetArchived>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.archived = false;
+
+        msg!("Counter unarchived");
+        Ok(())
+    }
+
+    /// Fail unless the counter's layout version matches `expected_version`
+    ///
+    /// Clients prepend this to a transaction so that an outdated client
+    /// cannot write to an account in a newer format.
+    pub fn check_version(ctx: Context<GetCount>, expected_version: u8) -> Result<()> {
+        require!(
+            ctx.accounts.counter.version == expected_version,
+            ErrorCode::VersionMismatch
+        );
+        Ok(())
+    }
+
+    /// Get current counter value (view function)
+    ///
+    /// A private counter needs `reader` to sign as its authority or one of
+    /// its readers. This only gates the instruction; the account data itself
+    /// is still public.
+    pub fn get_count(ctx: Context<GetCount>) -> Result<u64> {
+        let counter = &ctx.accounts.counter;
+        counter.assert_reader(ctx.accounts.reader.as_ref().map(|reader| reader.key()))?;
+        Ok(counter.count)
+    }
+
+    /// Get the signer behind the most recent value change (view function)
+    pub fn get_last_modifier(ctx: Context<GetCount>) -> Result<Pubkey> {
+        Ok(ctx.accounts.counter.last_modifier)
+    }
+
+    /// Read the count straight from the account bytes (view function)
+    ///
+    /// Only the discriminator and the `authority` and `count` prefix are
+    /// relied on, so forks that append or reorder later fields still work.
+    pub fn get_count_raw(ctx: Context<GetCountRaw>) -> Result<u64> {
+        validate_counter(&ctx.accounts.counter)?;
+        let data = ctx.accounts.counter.try_borrow_data()?;
+        let bytes = data
+            .get(40..48)
+            .ok_or(anchor_lang::error::ErrorCode::AccountDidNotDeserialize)?;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    /// Get `numerator / denominator` of two counters in basis points (view
+    /// function)
+    pub fn get_ratio(ctx: Context<GetRatio>) -> Result<u64> {
+        let denominator = ctx.accounts.denominator.count;
+        require!(denominator != 0, ErrorCode::DivideByZero);
+
+        let ratio = ctx.accounts.numerator.count as u128 * TOTAL_BPS as u128
+            / denominator as u128;
+        Ok(u64::try_from(ratio).map_err(|_| ErrorCode::MulOverflow)?)
+    }
+
+    /// Seconds elapsed since the counter last changed, by cluster time
+    /// (view function)
+    pub fn seconds_since_update(ctx: Context<GetCount>) -> Result<i64> {
+        Ok(Clock::get()?
+            .unix_timestamp
+            .saturating_sub(ctx.accounts.counter.last_updated))
+    }
+
+    /// Get the unit the count is measured in (view function)
+    pub fn get_unit(ctx: Context<GetMeta>) -> Result<Unit> {
+        Ok(ctx.accounts.meta.unit)
+    }
+
+    /// Get the slot of the last update (view function)
+    pub fn get_last_slot(ctx: Context<GetCount>) -> Result<u64> {
+        Ok(ctx.accounts.counter.last_slot)
+    }
+
+    /// Seconds left until `end_ts`, or zero once it has passed (view function)
+    pub fn get_remaining(ctx: Context<GetCount>) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(ctx.accounts.counter.end_ts.saturating_sub(now).max(0) as u64)
+    }
+
+    /// Whether `who` is the authority or the delegate, so clients can gate
+    /// controls without attempting a mutation (view function)
+    pub fn can_modify(ctx: Context<GetCount>, who: Pubkey) -> Result<bool> {
+        Ok(ctx.accounts.counter.can_increment(&who))
+    }
+
+    /// Whether `claimed` equals the stored count, so an indexer can check a
+    /// value rebuilt from events (view function)
+    pub fn verify_count(ctx: Context<GetCount>, claimed: u64) -> Result<bool> {
+        Ok(ctx.accounts.counter.count == claimed)
+    }
+
+    /// Get the raw count with the decimals to scale it by (view function)
+    pub fn get_scaled_count(ctx: Context<GetCount>) -> Result<ScaledCount> {
+        let counter = &ctx.accounts.counter;
+        Ok(ScaledCount {
+            count: counter.count,
+            decimals: counter.decimals,
+        })
+    }
+
+    /// Get the lowest and highest counts seen since creation (view function)
+    pub fn get_seen_range(ctx: Context<GetCount>) -> Result<SeenRange> {
+        let counter = &ctx.accounts.counter;
+        Ok(SeenRange {
+            min_seen: counter.min_seen,
+            max_seen: counter.max_seen,
+        })
+    }
+
+    /// Whether the count is even (view function)
+    // `is_multiple_of` is newer than the platform-tools rustc used for SBF
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn get_parity(ctx: Context<GetCount>) -> Result<bool> {
+        Ok(ctx.accounts.counter.count % 2 == 0)
+    }
+
+    /// Get the recent values from oldest to newest (view function)
+    pub fn get_history(ctx: Context<GetCount>) -> Result<Vec<u64>> {
+        Ok(ctx.accounts.counter.history_chronological())
+    }
+
+    /// Get one history value, indexed like `get_history` with 0 the oldest
+    /// (view function)
+    pub fn get_count_at(ctx: Context<GetCount>, index: u8) -> Result<u64> {
+        ctx.accounts
+            .counter
+            .history_chronological()
+            .get(index as usize)
+            .copied()
+            .ok_or_else(|| error!(ErrorCode::IndexOutOfRange))
+    }
+
+    /// Emit the counter's current Merkle leaf for off-chain accumulation
+    ///
+    /// The leaf is `hash(counter || count || last_updated)` with integers
+    /// little endian, so it only changes when the counter does.
+    pub fn emit_leaf(ctx: Context<GetCount>) -> Result<()> {
+        let counter = &ctx.accounts.counter;
+        let leaf = hashv(&[
+            counter.key().as_ref(),
+            &counter.count.to_le_bytes(),
+            &counter.last_updated.to_le_bytes(),
+        ])
+        .to_bytes();
+
+        emit!(CounterLeaf {
+            counter: counter.key(),
+            leaf,
+            slot: Clock::get()?.slot,
+        });
+        Ok(())
+    }
+
+    /// Get the count with decay since the last update applied (view function)
+    pub fn get_effective_count(ctx: Context<GetCount>) -> Result<u64> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(ctx.accounts.counter.effective_count(now))
+    }
+
+    /// Preview the count an increment would produce now, running every
+    /// guard plus decay, caps and wrapping on a copy (view function)
+    ///
+    /// Nothing is persisted; a guard that would reject the increment fails
+    /// the simulation with the same error.
+    pub fn simulate_increment(ctx: Context<GetCount>) -> Result<u64> {
+        let mut preview = ctx.accounts.counter.clone().into_inner();
+        preview.apply_increment(1, Clock::get()?.unix_timestamp)?;
+        Ok(preview.count)
+    }
+
+    /// Get the time-weighted accumulator accrued up to now (view function)
+    ///
+    /// Dividing the difference between two readings by the seconds between
+    /// them gives the average value over that window.
+    pub fn get_accumulator(ctx: Context<GetCount>) -> Result<u128> {
+        let now = Clock::get()?.unix_timestamp;
+        Ok(ctx.accounts.counter.accumulated_at(now))
+    }
+
+    /// Sum the counts of every counter passed in `remaining_accounts`
+    ///
+    /// Each account must be a counter owned by this program; anything else,
+    /// or a total past `u64::MAX`, fails the whole call.
+    pub fn sum_counters<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumCounters<'info>>,
+    ) -> Result<u64> {
+        let mut total: u64 = 0;
+        for account_info in ctx.remaining_accounts.iter() {
+            // Verifies program ownership and the account discriminator
+            let counter = Account::<Counter>::try_from(account_info)?;
+            total = total.checked_add(counter.count).ok_or(ErrorCode::AddOverflow)?;
+        }
+        Ok(total)
+    }
+
+    /// Get the counts of every counter passed in `remaining_accounts`, in
+    /// order (view function)
+    pub fn read_many<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumCounters<'info>>,
+    ) -> Result<Vec<u64>> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_READ_MANY,
+            ErrorCode::TooManyCounters
+        );
+        ctx.remaining_accounts
+            .iter()
+            // Verifies program ownership and the account discriminator
+            .map(|account_info| Ok(Account::<Counter>::try_from(account_info)?.count))
+            .collect()
+    }
+
+    /// Get the addresses of the counters in `remaining_accounts` whose
+    /// authority is `target`, in order (view function)
+    pub fn filter_by_authority<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SumCounters<'info>>,
+        target: Pubkey,
+    ) -> Result<Vec<Pubkey>> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_FILTER_ACCOUNTS,
+            ErrorCode::TooManyCounters
+        );
+        let mut matches = Vec::new();
+        for account_info in ctx.remaining_accounts.iter() {
+            // Verifies program ownership and the account discriminator
+            let counter = Account::<Counter>::try_from(account_info)?;
+            if counter.authority == target {
+                matches.push(counter.key());
+            }
+        }
+        Ok(matches)
+    }
+
+    /// Get the default counter PDA and its canonical bump for `authority`
+    /// (view function)
+    pub fn derive_pda(_ctx: Context<DerivePda>, authority: Pubkey) -> Result<DerivedPda> {
+        let (address, bump) =
+            Pubkey::find_program_address(&[b"counter", authority.as_ref()], &crate::ID);
+        Ok(DerivedPda { address, bump })
+    }
+
+    /// Create a group PDA that counters can join at creation
+    pub fn initialize_group(ctx: Context<InitializeGroup>, group_id: u64) -> Result<()> {
+        let group = &mut ctx.accounts.group;
+        group.authority = ctx.accounts.authority.key();
+        group.group_id = group_id;
+        group.bump = ctx.bumps.group;
+
+        msg!("Group {} created", group_id);
+        Ok(())
+    }
+
+    /// Sum the counts of the group's counters passed in `remaining_accounts`
+    ///
+    /// Every account must be a counter whose `parent` is `group`.
+    pub fn group_sum<'info>(ctx: Context<'_, '_, 'info, 'info, GroupSum<'info>>) -> Result<u64> {
+        let group = ctx.accounts.group.key();
+        let mut total: u64 = 0;
+        for account_info in ctx.remaining_accounts.iter() {
+            // Verifies program ownership and the account discriminator
+            let counter = Account::<Counter>::try_from(account_info)?;
+            require_keys_eq!(counter.parent, group, ErrorCode::NotInGroup);
+            total = total.checked_add(counter.count).ok_or(ErrorCode::AddOverflow)?;
+        }
+        Ok(total)
+    }
+
+    /// Get a full snapshot of the counter account (view function)
+    ///
+    /// The return data is the Borsh-encoded `Counter` struct without the
+    /// 8-byte account discriminator, so clients decode it with the IDL's
+    /// `Counter` type exactly as they would a fetched account.
+    pub fn get_counter_info(ctx: Context<GetCount>) -> Result<Counter> {
+        Ok(ctx.accounts.counter.clone().into_inner())
+    }
+
+    /// Get the program-wide usage stats kept in the config (view function)
+    pub fn get_global_stats(ctx: Context<GetGlobalStats>) -> Result<GlobalStats> {
+        let config = &ctx.accounts.config;
+        Ok(GlobalStats {
+            total_counters_created: config.total_counters_created,
+            total_increments: config.total_increments,
+            total_closed: config.total_closed,
+        })
+    }
+
+    /// Get the current value alongside lifetime increment count (view function)
+    pub fn get_stats(ctx: Context<GetCount>) -> Result<CounterStats> {
+        let counter = &ctx.accounts.counter;
+        Ok(CounterStats {
+            count: counter.count,
+            total_increments: counter.total_increments,
+        })
+    }
+
+    /// Get the current value with the health flags monitoring checks, all
+    /// evaluated against the current clock (view function)
+    ///
+    /// `rate_limited` runs the throttles on a copy, as `simulate_increment`
+    /// does, so an empty token bucket, a tripped breaker or a used-up daily
+    /// cap all count. `paused` includes the global pause when the config
+    /// is passed.
+    pub fn get_status(ctx: Context<GetStatus>) -> Result<CounterStatus> {
+        let counter = &ctx.accounts.counter;
+        let now = Clock::get()?.unix_timestamp;
+        let globally_paused = ctx
+            .accounts
+            .config
+            .as_ref()
+            .is_some_and(|config| config.global_pause);
+        let mut preview = counter.clone().into_inner();
+        let rate_limited =
+            preview.check_rate_limit(now).is_err() || preview.consume_allowance(now).is_err();
+        Ok(CounterStatus {
+            count: counter.count,
+            paused: counter.is_paused || now < counter.paused_until || globally_paused,
+            archived: counter.archived,
+            rate_limited,
+            cap_reached: counter.max_value != 0 && counter.count >= counter.max_value,
+        })
+    }
+
+    /// Create the authority's signed counter, which may go below zero
+    pub fn initialize_signed_counter(
+        ctx: Context<InitializeSignedCounter>,
+        initial_value: i64,
+    ) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.authority = ctx.accounts.authority.key();
+        counter.value = initial_value;
+        counter.last_updated = Clock::get()?.unix_timestamp;
+        counter.bump = ctx.bumps.counter;
+
+        msg!("Signed counter initialized with value: {}", initial_value);
+        Ok(())
+    }
+
+    /// Add one to a signed counter
+    pub fn increment_signed_counter(ctx: Context<UpdateSignedCounter>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.value = counter.value.checked_add(1).ok_or(ErrorCode::AddOverflow)?;
+        counter.last_updated = Clock::get()?.unix_timestamp;
+
+        msg!("Signed counter incremented to: {}", counter.value);
+        Ok(())
+    }
+
+    /// Subtract one from a signed counter, going negative if needed
+    pub fn decrement_signed_counter(ctx: Context<UpdateSignedCounter>) -> Result<()> {
+        let counter = &mut ctx.accounts.counter;
+        counter.value = counter.value.checked_sub(1).ok_or(ErrorCode::Underflow)?;
+        counter.last_updated = Clock::get()?.unix_timestamp;
+
+        msg!("Signed counter decremented to: {}", counter.value);
+        Ok(())
+    }
+}
+
+// ========================================
+// HELPERS
+// ========================================
+
+/// Add a newly created counter to its authority's registry
+fn register_counter(
+    registry: &mut Account<Registry>,
+    authority: Pubkey,
+    counter: Pubkey,
+    bump: u8,
+) -> Result<()> {
+    require!(
+        registry.counters.len() < MAX_REGISTRY_COUNTERS,
+        ErrorCode::RegistryFull
+    );
+    registry.authority = authority;
+    registry.bump = bump;
+    registry.counters.push(counter);
+    Ok(())
+}
+
+/// Shared setup for every counter creation path
+///
+/// When the config's allowlist is non-empty, `authority` must be on it.
+fn init_counter(
+    counter: &mut Account<Counter>,
+    authority: Pubkey,
+    initial_value: u64,
+    params: InitializeParams,
+    config: &mut Config,
+) -> Result<()> {
+    let max_value = params
+        .max_value
+        .unwrap_or(config.default_max_value);
+    let min_interval_seconds = params
+        .min_interval_seconds
+        .unwrap_or(config.default_min_interval);
+    let min_value = params.min_value.unwrap_or(0);
+    let wrap = params.wrap.unwrap_or(false);
+    let freeze_authority = params.freeze_authority.unwrap_or(authority);
+    let decay_per_second = params.decay_per_second.unwrap_or(0);
+    let cosigner = params.cosigner.unwrap_or_default();
+    let max_change_bps = params.max_change_bps.unwrap_or(0);
+    let overflow_policy = params.overflow_policy.unwrap_or(if wrap {
+        OverflowPolicy::Wrap
+    } else {
+        OverflowPolicy::Error
+    });
+
+    require!(
+        config.allowlist.is_empty() || config.allowlist.contains(&authority),
+        ErrorCode::NotAllowlisted
+    );
+    require!(
+        max_value == 0 || min_value <= max_value,
+        ErrorCode::InvalidBounds
+    );
+
+    counter.authority = authority;
+    counter.max_value = max_value;
+    counter.min_interval_seconds = min_interval_seconds;
+    counter.min_value = min_value;
+    counter.wrap = overflow_policy == OverflowPolicy::Wrap;
+    counter.overflow_policy = overflow_policy;
+    counter.password_hash = params.password_hash.unwrap_or_default();
+    counter.max_transfers = params.max_transfers.unwrap_or(0);
+    counter.decimals = params.decimals.unwrap_or(0);
+    counter.freeze_authority = freeze_authority;
+    counter.decay_per_second = decay_per_second;
+    counter.cosigner = cosigner;
+    counter.max_change_bps = max_change_bps;
+    counter.check_max(initial_value)?;
+    counter.check_min(initial_value)?;
+    counter.count = initial_value;
+    counter.min_seen = initial_value;
+    counter.max_seen = initial_value;
+    counter.last_updated = Clock::get()?.unix_timestamp;
+    counter.last_slot = Clock::get()?.slot;
+    counter.created_at = counter.last_updated;
+    counter.version = COUNTER_VERSION;
+    counter.last_modifier = authority;
+    config.total_counters_created = config.total_counters_created.saturating_add(1);
+
+    msg!("Counter initialized with value: {}", initial_value);
+    emit!(CounterInitialized {
+        counter: counter.key(),
+        authority,
+        count: counter.count,
+        timestamp: counter.last_updated,
+    });
+    Ok(())
+}
+
+/// Check that `account_info` is owned by this program and carries the
+/// `Counter` discriminator, without deserializing it
+///
+/// Public so integrating programs can reject the wrong account type before
+/// building a CPI.
+pub fn validate_counter(account_info: &AccountInfo) -> Result<()> {
+    require_keys_eq!(
+        *account_info.owner,
+        crate::ID,
+        anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram
+    );
+    require!(
+        account_info.try_borrow_data()?.starts_with(&Counter::DISCRIMINATOR),
+        anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch
+    );
+    Ok(())
+}
+
+/// Split a counter serialized with the version 1 layout, which held label,
+/// note, tags and unit inline, into the current layout and that metadata
+///
+/// `data` is zero-padded past the end of shorter, older layouts, so any
+/// field they lack reads as empty.
+fn split_legacy_counter(data: &[u8]) -> Result<(Vec<u8>, CounterMeta)> {
+    // Fixed-size runs of the version 1 layout between its variable fields
+    const BEFORE_LABEL: usize = 113; // Discriminator through min_interval_seconds
+    const LABEL_TO_NOTE: usize = 365; // delegate through paused_until
+    const NOTE_TO_TAGS: usize = 74; // cosigner through vote_round
+    const TAGS_TO_READERS: usize = 180; // overflow_policy through breaker_until
+    const READERS_TO_UNIT: usize = 92; // private through last_slot
+
+    fn keep(current: &mut Vec<u8>, rest: &mut &[u8], len: usize) -> Result<()> {
+        require!(
+            rest.len() >= len,
+            anchor_lang::error::ErrorCode::AccountDidNotDeserialize
+        );
+        let (kept, tail) = rest.split_at(len);
+        current.extend_from_slice(kept);
+        *rest = tail;
+        Ok(())
+    }
+
+    let mut current = Vec::with_capacity(data.len());
+    let mut rest = data;
+    let mut meta = CounterMeta::default();
+    keep(&mut current, &mut rest, BEFORE_LABEL)?;
+    meta.label = String::deserialize(&mut rest)?;
+    keep(&mut current, &mut rest, LABEL_TO_NOTE)?;
+    meta.note = String::deserialize(&mut rest)?;
+    keep(&mut current, &mut rest, NOTE_TO_TAGS)?;
+    meta.tags = Vec::<String>::deserialize(&mut rest)?;
+    keep(&mut current, &mut rest, TAGS_TO_READERS)?;
+    // Readers stay on the counter; keep exactly the bytes they take up
+    let readers_len = 4 + 32 * Vec::<Pubkey>::deserialize(&mut &rest[..])?.len();
+    keep(&mut current, &mut rest, readers_len + READERS_TO_UNIT)?;
+    meta.unit = Unit::deserialize(&mut rest)?;
+    current.extend_from_slice(rest);
+    Ok((current, meta))
+}
+
+/// Create the `[b"meta", counter]` PDA holding `contents`, paid by `payer`
+fn create_meta<'info>(
+    meta: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    counter: Pubkey,
+    bump: u8,
+    contents: CounterMeta,
+) -> Result<()> {
+    let space = CounterMeta::space_for(&contents.tags);
+    system_program::create_account(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            system_program::CreateAccount {
+                from: payer.clone(),
+                to: meta.clone(),
+            },
+            &[&[b"meta", counter.as_ref(), &[bump]]],
+        ),
+        Rent::get()?.minimum_balance(space),
+        space as u64,
+        &crate::ID,
+    )?;
+
+    let contents = CounterMeta {
+        counter,
+        bump,
+        ..contents
+    };
+    contents.try_serialize(&mut &mut meta.try_borrow_mut_data()?[..])
+}
+
+/// Count an increment in the config's program-wide stats
+///
+/// Every handler that applies an increment calls this, so the total covers
+/// batch, CPI, paid, voted and cranked increments alike.
+fn record_increment(config: &mut Config) {
+    config.total_increments = config.total_increments.saturating_add(1);
+}
+
+/// Emit `MilestoneReached` for every milestone the count has just crossed
+///
+/// Called after each increment and `set_count`, so a milestone fires on
+/// the change that crosses it rather than on a later plain increment.
+fn emit_reached_milestones(counter: &mut Account<Counter>) {
+    for milestone in counter.take_reached_milestones() {
+        msg!("Counter reached milestone: {}", milestone);
+        emit!(MilestoneReached {
+            counter: counter.key(),
+            milestone,
+            count: counter.count,
+            timestamp: counter.last_updated,
+        });
+    }
+}
+
+/// Pay the counter's bounty from its vault to the incrementing signer
+///
+/// The vault keeps its rent-exempt minimum, so payouts stop with
+/// `BountyExhausted` once only that reserve is left.
+fn pay_bounty(accounts: &Increment, vault_bump: u8) -> Result<()> {
+    let bounty = accounts.counter.bounty_lamports;
+    if bounty == 0 {
+        return Ok(());
+    }
+
+    let available = accounts
+        .bounty_vault
+        .lamports()
+        .saturating_sub(Rent::get()?.minimum_balance(0));
+    require!(available >= bounty, ErrorCode::BountyExhausted);
+
+    let counter_key = accounts.counter.key();
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: accounts.bounty_vault.to_account_info(),
+                to: accounts.authority.to_account_info(),
+            },
+            &[&[b"bounty", counter_key.as_ref(), &[vault_bump]]],
+        ),
+        bounty,
+    )?;
+    assert_rent_exempt(&accounts.bounty_vault.to_account_info())?;
+
+    msg!("Paid bounty of {} lamports", bounty);
+    Ok(())
+}
+
+/// Mark the counter locked and write it back before an outbound CPI, so a
+/// call that reenters this program during the CPI sees the lock and every
+/// mutation fails with `Reentrancy`
+///
+/// The runtime already rejects indirect reentrancy (this program -> other
+/// -> this program) and only permits a program to invoke itself, so today
+/// this guards our own self-CPI paths, and any CPI whose target becomes
+/// caller-supplied later. The caller clears `locked` once the CPI returns;
+/// a failed CPI aborts the transaction, so the lock never persists.
+fn lock_counter(counter: &mut Account<Counter>) -> Result<()> {
+    require!(!counter.locked, ErrorCode::Reentrancy);
+    counter.locked = true;
+    counter.exit(&crate::ID)
+}
+
+/// Require `account_info` to hold at least the rent-exempt minimum for its
+/// size; run after any instruction moves lamports out of a program account
+fn assert_rent_exempt(account_info: &AccountInfo) -> Result<()> {
+    require!(
+        Rent::get()?.is_exempt(account_info.lamports(), account_info.data_len()),
+        ErrorCode::WouldBeNonRentExempt
+    );
+    Ok(())
+}
+
+/// Require the fee split to be non-empty with shares summing to 10000 bps
+fn validate_fee_split(fee_recipients: &[FeeRecipient]) -> Result<()> {
+    let total = fee_recipients
+        .iter()
+        .try_fold(0u16, |total, recipient| total.checked_add(recipient.bps));
+    require!(total == Some(TOTAL_BPS), ErrorCode::InvalidSplit);
+    Ok(())
+}
+
+/// Read the balance of `token_account`, which must be an SPL token account
+/// for `mint` owned by `owner`
+fn token_balance(token_account: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> Result<u64> {
+    // SPL token account layout: mint (32), owner (32), amount (u64 LE), ...
+    let data = token_account.try_borrow_data()?;
+    require!(data.len() >= 72, ErrorCode::NotTokenHolder);
+    require!(
+        data[0..32] == mint.to_bytes() && data[32..64] == owner.to_bytes(),
+        ErrorCode::NotTokenHolder
+    );
+
+    let amount = u64::from_le_bytes(
+        data[64..72].try_into().map_err(|_| ErrorCode::NotTokenHolder)?,
+    );
+    Ok(amount)
+}
+
+/// Mint `amount` tokens to `token_account` with a CPI to the SPL Token
+/// program, signing as the PDA `mint_authority` with `signer_seeds`
+fn mint_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    mint_authority: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    // SPL Token `MintTo`: tag 7 followed by the amount (u64, little endian)
+    let mut data = vec![7u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(mint.key(), false),
+            AccountMeta::new(token_account.key(), false),
+            AccountMeta::new_readonly(mint_authority.key(), true),
+        ],
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[
+            mint.clone(),
+            token_account.clone(),
+            mint_authority.clone(),
+            token_program.clone(),
+        ],
+        &[signer_seeds],
+    )?;
+    Ok(())
+}
+
+/// Burn `amount` tokens from `token_account` with a CPI to the SPL Token
+/// program; `owner` must have signed the outer transaction
+fn burn_tokens<'info>(
+    token_program: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    mint: &AccountInfo<'info>,
+    owner: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    // SPL Token `Burn`: tag 8 followed by the amount (u64, little endian)
+    let mut data = vec![8u8];
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(token_account.key(), false),
+            AccountMeta::new(mint.key(), false),
+            AccountMeta::new_readonly(owner.key(), true),
+        ],
+        data,
+    };
+    invoke(
+        &ix,
+        &[
+            token_account.clone(),
+            mint.clone(),
+            owner.clone(),
+            token_program.clone(),
+        ],
+    )?;
+    Ok(())
+}
+
+/// Check that `ix` is an Ed25519 program instruction verifying exactly one
+/// `signature` by `signer` over `message`, with all data stored inline
+fn verify_ed25519_instruction(
+    ix: &Instruction,
+    signer: &Pubkey,
+    signature: &[u8; 64],
+    message: &[u8],
+) -> Result<()> {
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::MissingSignatureInstruction
+    );
+
+    // Layout: [num_signatures: u8, padding: u8, offsets: 7 x u16, data...]
+    let data = &ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        ErrorCode::InvalidSignatureInstruction
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let slice = |offset: u16, len: usize| {
+        data.get(offset as usize..offset as usize + len)
+            .ok_or(ErrorCode::InvalidSignatureInstruction)
+    };
+
+    let signature_offset = read_u16(2);
+    let public_key_offset = read_u16(6);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12) as usize;
+
+    // u16::MAX means the data lives in the Ed25519 instruction itself
+    require!(
+        read_u16(4) == u16::MAX && read_u16(8) == u16::MAX && read_u16(14) == u16::MAX,
+        ErrorCode::InvalidSignatureInstruction
+    );
+    require!(
+        slice(public_key_offset, 32)? == signer.as_ref()
+            && slice(signature_offset, 64)? == signature.as_slice()
+            && message_size == message.len()
+            && slice(message_offset, message_size)? == message,
+        ErrorCode::InvalidSignatureInstruction
+    );
+    Ok(())
+}
+
+// ========================================
+// ACCOUNT STRUCTURES
+// ========================================
+
+/// Counter account structure
+///
+/// `INIT_SPACE` is derived from the fields, so adding one cannot leave the
+/// allocated size behind. Label, note, tags and unit live in the
+/// counter's `CounterMeta` so that increments write less data.
+#[account]
+#[derive(Default, InitSpace)]
+pub struct Counter {
+    pub authority: Pubkey,    // 32 bytes - Who can modify this counter
+    pub count: u64,           // 8 bytes  - Current count value
+    pub last_updated: i64,    // 8 bytes  - Unix timestamp of last update
+    pub max_value: u64,       // 8 bytes  - Upper bound for count (0 = no cap)
+    pub is_paused: bool,      // 1 byte   - Blocks mutations while set
+    pub pending_authority: Pubkey, // 32 bytes - Proposed authority awaiting acceptance
+    pub total_increments: u64, // 8 bytes - Number of increment operations ever applied
+    pub min_interval_seconds: i64, // 8 bytes - Minimum gap between increments (0 = no limit)
+    pub delegate: Pubkey,     // 32 bytes - May increment on the authority's behalf
+    pub min_value: u64,       // 8 bytes  - Floor that decrements cannot cross
+    pub checkpoint: u64,      // 8 bytes  - Saved value for restore_checkpoint
+    pub authorities: [Pubkey; MAX_AUTHORITIES], // 96 bytes - Co-owners for threshold approvals
+    pub threshold: u8,        // 1 byte   - Co-owner approvals required to transfer (0 = off)
+    pub created_at: i64,      // 8 bytes  - Unix timestamp of creation, never modified
+    pub nonce: u64,           // 8 bytes  - Replay protection for increment_signed
+    pub version: u8,          // 1 byte   - Account layout version (COUNTER_VERSION)
+    pub last_modifier: Pubkey, // 32 bytes - Authority or delegate behind the last value change
+    pub history: [u64; HISTORY_LEN], // 64 bytes - Values after recent increments/decrements
+    pub history_head: u8,     // 1 byte   - Next slot to overwrite in `history`
+    pub wrap: bool,           // 1 byte   - Roll over to min_value instead of failing at the cap
+    pub accumulator: u128,    // 16 bytes - Integral of count over time (value-seconds)
+    pub freeze_authority: Pubkey, // 32 bytes - May pause/unpause, but not change the value
+    pub bounty_lamports: u64, // 8 bytes  - Paid from the bounty vault to each incrementer
+    pub state_hash: [u8; 32], // 32 bytes - Rolling hash of every increment/decrement state
+    pub archived: bool,       // 1 byte   - Read-only; all mutations fail while set
+    pub decay_per_second: u64, // 8 bytes - Amount the count loses per second since last_updated
+    pub paused_until: i64,    // 8 bytes  - Mutations fail until this timestamp
+    pub cosigner: Pubkey,     // 32 bytes - Must also sign increments; default disables
+    pub max_change_bps: u16,  // 2 bytes  - Largest set_count move as bps of the count (0 = off)
+    pub vote_mint: Pubkey,    // 32 bytes - Token whose balance weights votes
+    pub vote_round: u64,      // 8 bytes  - Current voting round; zero until one is opened
+    pub overflow_policy: OverflowPolicy, // 1 byte - What increments do past the cap
+    pub password_hash: [u8; 32], // 32 bytes - SHA-256 of the increment password; zero disables
+    pub milestones: [u64; 4], // 32 bytes - Counts that emit MilestoneReached; zero slots are unused
+    pub milestones_fired: u8, // 1 byte   - Bitmask of milestones already reached
+    pub bucket_capacity: u64, // 8 bytes  - Most increments allowed in a burst (0 = no bucket)
+    pub refill_per_second: u64, // 8 bytes - Tokens added back per elapsed second
+    pub tokens: u64,          // 8 bytes  - Increments currently available
+    pub last_refill: i64,     // 8 bytes  - When `tokens` was last topped up
+    pub transfer_count: u8,   // 1 byte   - Authority transfers made so far
+    pub max_transfers: u8,    // 1 byte   - Transfers allowed in total (0 = unlimited)
+    pub expires_after: i64,   // 8 bytes  - Idle seconds before anyone may close it (0 = never)
+    pub parent: Pubkey,       // 32 bytes - Group this counter belongs to; default for none
+    pub max_per_window: u32,  // 4 bytes  - Increments per window that trip the breaker (0 = off)
+    pub window_seconds: i64,  // 8 bytes  - Length of the breaker's counting window
+    pub cooldown_seconds: i64, // 8 bytes - How long a tripped breaker blocks increments
+    pub increments_in_window: u32, // 4 bytes - Increments counted in the current window
+    pub window_start: i64,    // 8 bytes  - When the current window began
+    pub breaker_until: i64,   // 8 bytes  - Increments fail until this timestamp
+    #[max_len(MAX_READERS)]
+    pub readers: Vec<Pubkey>, // 4 + 32 * MAX_READERS bytes - Who besides the authority may get_count
+    pub private: bool,        // 1 byte   - Whether get_count is restricted to readers
+    pub pending_delta: u64,   // 8 bytes  - Staged increment awaiting finalize (0 = none)
+    pub decimals: u8,         // 1 byte   - Decimal places clients apply when displaying count
+    pub count_on_read: bool,  // 1 byte   - Whether read_and_count increments before returning
+    pub min_seen: u64,        // 8 bytes  - Lowest count since creation
+    pub max_seen: u64,        // 8 bytes  - Highest count since creation
+    pub target: u64,          // 8 bytes  - Count at which the escrow vault unlocks (0 = unset)
+    pub locked: bool,         // 1 byte   - Set while an outbound CPI is in flight
+    pub end_ts: i64,          // 8 bytes  - When the timer runs out (0 = no timer)
+    pub daily_cap: u64,       // 8 bytes  - Increments allowed per UTC day (0 = no cap)
+    pub used_today: u64,      // 8 bytes  - Increments made during `day_index`
+    pub day_index: i64,       // 8 bytes  - Days since the Unix epoch that `used_today` counts
+    pub transfer_timelock: i64, // 8 bytes - Seconds a proposed authority must wait to accept
+    pub transfer_effective_at: i64, // 8 bytes - When the pending authority may accept
+    pub last_slot: u64,       // 8 bytes  - Slot of the last update, stamped with `last_updated`
+    #[max_len(MAX_SCHEDULED)]
+    pub scheduled: Vec<i64>,  // 4 + 8 * MAX_SCHEDULED bytes - Times of queued increments
+    #[max_len(MAX_DELEGATES)]
+    pub delegates: Vec<Pubkey>, // 4 + 32 * MAX_DELEGATES bytes - Extra keys that may increment
+}
+
+impl Counter {
+    /// Bytes allocated for a new counter, including the discriminator
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// Whether `signer` may increment: the authority, its delegate or one of
+    /// its listed delegates
+    pub fn can_increment(&self, signer: &Pubkey) -> bool {
+        *signer == self.authority
+            || (self.delegate != Pubkey::default() && *signer == self.delegate)
+            || self.delegates.contains(signer)
+    }
+
+    /// Reject a jump to `value` larger than `max_change_bps` of the current
+    /// count; from zero, any change is too large while the guard is on
+    pub fn check_change(&self, value: u64) -> Result<()> {
+        if self.max_change_bps > 0 {
+            let delta = self.count.abs_diff(value) as u128;
+            let allowed = self.count as u128 * self.max_change_bps as u128 / TOTAL_BPS as u128;
+            require!(delta <= allowed, ErrorCode::ChangeTooLarge);
+        }
+        Ok(())
+    }
+
+    /// Mark every milestone the count has reached for the first time and
+    /// return them; each milestone fires at most once until reconfigured
+    pub fn take_reached_milestones(&mut self) -> Vec<u64> {
+        let mut reached = Vec::new();
+        for (i, milestone) in self.milestones.iter().enumerate() {
+            let bit = 1u8 << i;
+            if *milestone != 0 && self.count >= *milestone && self.milestones_fired & bit == 0 {
+                self.milestones_fired |= bit;
+                reached.push(*milestone);
+            }
+        }
+        reached
+    }
+
+    /// Hand the counter to `new_authority`, returning the previous one
+    ///
+    /// Every path that changes `authority` goes through here, so the transfer
+    /// is counted and any pending proposal is dropped; otherwise a nominee
+    /// from before a direct transfer could still accept and take the counter.
+    pub fn transfer_to(&mut self, new_authority: Pubkey) -> Result<Pubkey> {
+        self.record_transfer()?;
+        let previous_authority = self.authority;
+        self.authority = new_authority;
+        self.pending_authority = Pubkey::default();
+        self.transfer_effective_at = 0;
+        Ok(previous_authority)
+    }
+
+    /// Count an authority transfer against `max_transfers`
+    pub fn record_transfer(&mut self) -> Result<()> {
+        require!(
+            self.max_transfers == 0 || self.transfer_count < self.max_transfers,
+            ErrorCode::TransferLimitReached
+        );
+        // Unlimited counters may transfer more than 255 times
+        self.transfer_count = self.transfer_count.saturating_add(1);
+        Ok(())
+    }
+
+    /// Allow reads of a private counter only by the authority or a reader
+    pub fn assert_reader(&self, reader: Option<Pubkey>) -> Result<()> {
+        if self.private {
+            require!(
+                reader.is_some_and(|key| key == self.authority || self.readers.contains(&key)),
+                ErrorCode::NotAuthorizedReader
+            );
+        }
+        Ok(())
+    }
+
+    /// Whether increments need a second signature from `cosigner`
+    pub fn requires_cosigner(&self) -> bool {
+        self.cosigner != Pubkey::default()
+    }
+
+    /// Reject mutations while the counter is archived or frozen
+    pub fn assert_mutable(&self) -> Result<()> {
+        self.assert_not_archived()?;
+        require!(!self.locked, ErrorCode::Reentrancy);
+        require!(!self.is_paused, ErrorCode::CounterPaused);
+        require!(
+            Clock::get()?.unix_timestamp >= self.paused_until,
+            ErrorCode::CounterPaused
+        );
+        Ok(())
+    }
+
+    /// Reject any change, including settings, to an archived counter
+    pub fn assert_not_archived(&self) -> Result<()> {
+        require!(!self.archived, ErrorCode::CounterArchived);
+        Ok(())
+    }
+
+    /// Reject values above the configured cap
+    pub fn check_max(&self, value: u64) -> Result<()> {
+        if self.max_value != 0 && value > self.max_value {
+            msg!("Value {} exceeds max_value {}", value, self.max_value);
+            return err!(ErrorCode::MaxValueReached);
+        }
+        Ok(())
+    }
+
+    /// Reject values below the configured floor
+    pub fn check_min(&self, value: u64) -> Result<()> {
+        if value < self.min_value {
+            msg!("Value {} is below min_value {}", value, self.min_value);
+            return err!(ErrorCode::MinValueReached);
+        }
+        Ok(())
+    }
+
+    /// Require `threshold` distinct co-owners to have signed, counting the
+    /// primary authority signer and any signer in `others`
+    pub fn check_threshold<'info>(
+        &self,
+        authority: &AccountInfo<'info>,
+        others: &[AccountInfo<'info>],
+    ) -> Result<()> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+
+        let mut approved = [false; MAX_AUTHORITIES];
+        for info in std::iter::once(authority).chain(others.iter()) {
+            if !info.is_signer {
+                continue;
+            }
+            for (slot, member) in self.authorities.iter().enumerate() {
+                if *member != Pubkey::default() && info.key == member {
+                    approved[slot] = true;
+                }
+            }
+        }
+
+        let approvals = approved.iter().filter(|a| **a).count();
+        require!(
+            approvals >= self.threshold as usize,
+            ErrorCode::InsufficientSigners
+        );
+        Ok(())
+    }
+
+    /// Reject increments arriving before `min_interval_seconds` has elapsed
+    /// since the last update. The very first increment is never limited.
+    pub fn check_rate_limit(&self, now: i64) -> Result<()> {
+        if self.min_interval_seconds > 0 && self.total_increments > 0 {
+            let elapsed = now.saturating_sub(self.last_updated);
+            require!(
+                elapsed >= self.min_interval_seconds,
+                ErrorCode::RateLimited
+            );
+        }
+        Ok(())
+    }
+
+    /// Refill the token bucket for the time elapsed since `last_refill`, then
+    /// spend one token. A counter without a bucket is never limited here.
+    pub fn consume_token(&mut self, now: i64) -> Result<()> {
+        if self.bucket_capacity == 0 {
+            return Ok(());
+        }
+        let elapsed = now.saturating_sub(self.last_refill).max(0) as u64;
+        self.tokens = self
+            .tokens
+            .saturating_add(elapsed.saturating_mul(self.refill_per_second))
+            .min(self.bucket_capacity);
+        self.last_refill = now;
+
+        require!(self.tokens > 0, ErrorCode::RateLimited);
+        self.tokens -= 1;
+        Ok(())
+    }
+
+    /// Count an increment against today's cap, starting a fresh allowance
+    /// when the UTC day has changed since the last one
+    pub fn consume_daily(&mut self, now: i64) -> Result<()> {
+        if self.daily_cap == 0 {
+            return Ok(());
+        }
+        let today = now.div_euclid(SECONDS_PER_DAY);
+        if today != self.day_index {
+            self.day_index = today;
+            self.used_today = 0;
+        }
+
+        require!(self.used_today < self.daily_cap, ErrorCode::DailyCapReached);
+        self.used_today += 1;
+        Ok(())
+    }
+
+    /// Count an increment in the breaker's window, rejecting it while the
+    /// breaker is tripped
+    ///
+    /// The increment that reaches `max_per_window` still lands and trips
+    /// the breaker, so it stays tripped for `cooldown_seconds`.
+    pub fn check_circuit_breaker(&mut self, now: i64) -> Result<()> {
+        if self.max_per_window == 0 {
+            return Ok(());
+        }
+        require!(now >= self.breaker_until, ErrorCode::CircuitBreakerTripped);
+
+        if now.saturating_sub(self.window_start) >= self.window_seconds {
+            self.window_start = now;
+            self.increments_in_window = 0;
+        }
+        self.increments_in_window = self.increments_in_window.saturating_add(1);
+
+        if self.increments_in_window >= self.max_per_window {
+            self.breaker_until = now.saturating_add(self.cooldown_seconds);
+            self.window_start = self.breaker_until;
+            self.increments_in_window = 0;
+            msg!("Circuit breaker tripped until {}", self.breaker_until);
+        }
+        Ok(())
+    }
+
+    /// Spend one increment from the token bucket, the circuit breaker's
+    /// window and today's cap, failing if any of them is exhausted
+    pub fn consume_allowance(&mut self, now: i64) -> Result<()> {
+        self.consume_token(now)?;
+        self.check_circuit_breaker(now)?;
+        self.consume_daily(now)
+    }
+
+    /// Add `amount` to the count, enforcing every increment guard
+    ///
+    /// Returns whether a wrapping counter rolled over to `min_value`.
+    pub fn apply_increment(&mut self, amount: u64, now: i64) -> Result<bool> {
+        self.check_rate_limit(now)?;
+        self.apply_increment_unthrottled(amount, now)
+    }
+
+    /// The overflow policy in force; the older `wrap` flag still selects
+    /// `Wrap` on counters created before the policy existed
+    pub fn effective_overflow_policy(&self) -> OverflowPolicy {
+        if self.wrap {
+            OverflowPolicy::Wrap
+        } else {
+            self.overflow_policy
+        }
+    }
+
+    /// Add `amount` to the count, enforcing every guard except the minimum
+    /// interval between increments
+    ///
+    /// The token bucket, circuit breaker and daily cap still apply, so
+    /// forced, voted and cranked increments count against them like any
+    /// other.
+    pub fn apply_increment_unthrottled(&mut self, amount: u64, now: i64) -> Result<bool> {
+        self.assert_mutable()?;
+        self.consume_allowance(now)?;
+        self.apply_decay(now);
+
+        // Overflow protection; the policy decides what happens past the cap
+        let policy = self.effective_overflow_policy();
+        let cap = if self.max_value != 0 { self.max_value } else { u64::MAX };
+        let (new_count, wrapped) = match (self.count.checked_add(amount), policy) {
+            (Some(value), OverflowPolicy::Wrap) if value > cap => (self.min_value, true),
+            (None, OverflowPolicy::Wrap) => (self.min_value, true),
+            (value, OverflowPolicy::Saturate) => (value.unwrap_or(u64::MAX).min(cap), false),
+            (Some(value), _) => (value, false),
+            (None, _) => {
+                msg!("Adding {} to {} overflows u64", amount, self.count);
+                return err!(ErrorCode::AddOverflow);
+            }
+        };
+        self.check_max(new_count)?;
+
+        self.write_count(new_count, now)?;
+        self.total_increments = self.total_increments.saturating_add(1);
+        self.push_history();
+        self.fold_state_hash();
+        Ok(wrapped)
+    }
+
+    /// Add `amount` to the count, clamping at `u64::MAX` instead of failing
+    pub fn apply_increment_saturating(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.assert_mutable()?;
+        self.apply_decay(now);
+        self.check_rate_limit(now)?;
+        self.consume_allowance(now)?;
+
+        let new_count = self.count.saturating_add(amount);
+        self.check_max(new_count)?;
+
+        self.write_count(new_count, now)?;
+        self.total_increments = self.total_increments.saturating_add(1);
+        self.push_history();
+        self.fold_state_hash();
+        Ok(())
+    }
+
+    /// Subtract `amount` from the count, enforcing every decrement guard
+    pub fn apply_decrement(&mut self, amount: u64, now: i64) -> Result<()> {
+        self.assert_mutable()?;
+        self.apply_decay(now);
+
+        // Underflow protection
+        let new_count = self.count.checked_sub(amount)
+            .ok_or(ErrorCode::Underflow)?;
+        self.check_min(new_count)?;
+
+        self.write_count(new_count, now)?;
+        self.push_history();
+        self.fold_state_hash();
+        Ok(())
+    }
+
+    /// The count after decaying since `last_updated`, never below `min_value`
+    pub fn effective_count(&self, now: i64) -> u64 {
+        let elapsed = now.saturating_sub(self.last_updated).max(0) as u64;
+        self.count
+            .saturating_sub(self.decay_per_second.saturating_mul(elapsed))
+            .max(self.min_value.min(self.count))
+    }
+
+    /// Write the decayed value back before a change is applied on top of it
+    pub fn apply_decay(&mut self, now: i64) {
+        self.count = self.effective_count(now);
+        self.track_extremes();
+    }
+
+    /// Set the count at `now`, accruing the old value's time first and
+    /// stamping the update
+    ///
+    /// Every change to the count goes through here or `apply_decay`, so
+    /// `min_seen`/`max_seen` cannot miss one.
+    pub fn write_count(&mut self, value: u64, now: i64) -> Result<()> {
+        self.accrue(now);
+        self.count = value;
+        self.last_updated = now;
+        self.last_slot = Clock::get()?.slot;
+        self.track_extremes();
+        Ok(())
+    }
+
+    /// Add `count * seconds since last_updated` to the time-weighted
+    /// accumulator; called before every change to the count or timestamp
+    pub fn accrue(&mut self, now: i64) {
+        self.accumulator = self.accumulated_at(now);
+    }
+
+    /// The accumulator as it would stand at `now`
+    pub fn accumulated_at(&self, now: i64) -> u128 {
+        let elapsed = now.saturating_sub(self.last_updated).max(0) as u128;
+        self.accumulator.saturating_add(self.count as u128 * elapsed)
+    }
+
+    /// Record the current count in the history ring buffer
+    pub fn push_history(&mut self) {
+        self.history[self.history_head as usize] = self.count;
+        self.history_head = ((self.history_head as usize + 1) % HISTORY_LEN) as u8;
+    }
+
+    /// Widen `min_seen`/`max_seen` to include the current count
+    pub fn track_extremes(&mut self) {
+        self.min_seen = self.min_seen.min(self.count);
+        self.max_seen = self.max_seen.max(self.count);
+    }
+
+    /// Fold the current count and timestamp into the rolling state hash:
+    /// `sha256(prev_hash || count || last_updated)`, integers little endian
+    pub fn fold_state_hash(&mut self) {
+        self.state_hash = hashv(&[
+            &self.state_hash,
+            &self.count.to_le_bytes(),
+            &self.last_updated.to_le_bytes(),
+        ])
+        .to_bytes();
+    }
+
+    /// History values from oldest to newest
+    pub fn history_chronological(&self) -> Vec<u64> {
+        let head = self.history_head as usize;
+        self.history[head..]
+            .iter()
+            .chain(self.history[..head].iter())
+            .copied()
+            .collect()
+    }
+}
+
+/// What an increment does when it would pass `max_value` (or `u64::MAX`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug, InitSpace)]
+pub enum OverflowPolicy {
+    /// Fail with `AddOverflow` or `MaxValueReached`
+    #[default]
+    Error,
+    /// Clamp at the cap
+    Saturate,
+    /// Roll over to `min_value`
+    Wrap,
+}
+
+/// What a counter's value measures; display metadata that never affects
+/// arithmetic
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug, InitSpace)]
+pub enum Unit {
+    /// A plain tally
+    #[default]
+    Count,
+    Seconds,
+    Bytes,
+    /// Anything else; the label can say what
+    Custom,
+}
+
+/// Global defaults and admin settings, stored at the `[b"config"]` PDA
+#[account]
+#[derive(Default)]
+pub struct Config {
+    pub admin: Pubkey,              // 32 bytes - May update the config
+    pub default_max_value: u64,     // 8 bytes  - max_value for counters that omit it
+    pub default_min_interval: i64,  // 8 bytes  - min_interval_seconds for counters that omit it
+    pub bump: u8,                   // 1 byte   - Canonical PDA bump
+    pub global_pause: bool,         // 1 byte   - Emergency freeze for every counter
+    pub treasury: Pubkey,           // 32 bytes - Receives increment_priority tips
+    pub gate_mint: Pubkey,          // 32 bytes - Token required by initialize_gated
+    pub fee_recipients: Vec<FeeRecipient>, // 4 + 34 * MAX_FEE_RECIPIENTS bytes - increment_paid split
+    pub allowlist: Vec<Pubkey>,     // 4 + 32 * MAX_ALLOWLIST bytes - Who may create counters; empty allows all
+    pub total_counters_created: u64, // 8 bytes - Counters created through init_counter
+    pub total_increments: u64,      // 8 bytes  - Increments applied across all counters
+    pub total_closed: u64,          // 8 bytes  - Counters closed across all authorities
+}
+
+impl Config {
+    // Calculate space: 8 (discriminator) + 32 + 8 + 8 + 1 + 1 + 32 + 32 + (4 + 34 * 4) + (4 + 32 * 16)
+    //                  + 8 + 8 + 8 = 802 bytes
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 1 + 1 + 32 + 32 + (4 + 34 * MAX_FEE_RECIPIENTS)
+        + (4 + 32 * MAX_ALLOWLIST) + 8 + 8 + 8;
+}
+
+/// One payee in the config's fee split
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct FeeRecipient {
+    pub recipient: Pubkey, // 32 bytes - Receives its share of increment_paid
+    pub bps: u16,          // 2 bytes  - Share in basis points
+}
+
+/// Optional settings accepted when creating a counter
+///
+/// `None` for `max_value` or `min_interval_seconds` uses the config
+/// default. `freeze_authority` defaults to the counter authority;
+/// `cosigner` defaults to none.
+/// `overflow_policy` takes precedence over `wrap`, which is kept for
+/// existing clients and means `Wrap` when the policy is omitted.
+/// `max_transfers` can only be set here, so the cap cannot be lifted later.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct InitializeParams {
+    pub max_value: Option<u64>,
+    pub min_interval_seconds: Option<i64>,
+    pub min_value: Option<u64>,
+    pub wrap: Option<bool>,
+    pub freeze_authority: Option<Pubkey>,
+    pub decay_per_second: Option<u64>,
+    pub cosigner: Option<Pubkey>,
+    pub max_change_bps: Option<u16>,
+    pub overflow_policy: Option<OverflowPolicy>,
+    pub password_hash: Option<[u8; 32]>,
+    pub max_transfers: Option<u8>,
+    pub decimals: Option<u8>,
+}
+
+/// Settings changed together by `configure`; `None` leaves a field as is
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct ConfigParams {
+    pub max_value: Option<u64>,
+    pub min_interval_seconds: Option<i64>,
+    pub label: Option<String>,
+}
+
+/// Addresses of the counters an authority has created, stored at the
+/// `[b"registry", authority]` PDA
+///
+/// Entries are added on creation and removed on close; transferring a
+/// counter's authority does not move it to the new owner's registry, so
+/// contexts that remove entries take the registry as optional.
+#[account]
+#[derive(Default)]
+pub struct Registry {
+    pub authority: Pubkey,    // 32 bytes - Owner of the listed counters
+    pub counters: Vec<Pubkey>, // 4 + 32 * MAX_REGISTRY_COUNTERS bytes - Counter addresses
+    pub bump: u8,             // 1 byte   - Canonical PDA bump
+}
+
+impl Registry {
+    // Calculate space: 8 (discriminator) + 32 + (4 + 32 * 16) + 1 = 557 bytes
+    pub const SPACE: usize = 8 + 32 + (4 + 32 * MAX_REGISTRY_COUNTERS) + 1;
+}
+
+/// A counter over `i64` for values that can go negative, stored at the
+/// `[b"signed-counter", authority]` PDA alongside any unsigned `Counter`
+#[account]
+#[derive(Default)]
+pub struct SignedCounter {
+    pub authority: Pubkey,    // 32 bytes - Who can update the value
+    pub value: i64,           // 8 bytes  - Current value
+    pub last_updated: i64,    // 8 bytes  - Unix timestamp of the last change
+    pub bump: u8,             // 1 byte   - Canonical PDA bump
+}
+
+impl SignedCounter {
+    // Calculate space: 8 (discriminator) + 32 + 8 + 8 + 1 = 57 bytes
+    pub const SPACE: usize = 8 + 32 + 8 + 8 + 1;
+}
+
+/// Proof that `voter` voted on `counter`, stored at the
+/// `[b"vote", counter, voter]` PDA and reused across rounds
+#[account]
+#[derive(Default)]
+pub struct VoteReceipt {
+    pub counter: Pubkey,      // 32 bytes - Counter voted on
+    pub voter: Pubkey,        // 32 bytes - Token holder who voted
+    pub round: u64,           // 8 bytes  - Last round this voter took part in
+    pub bump: u8,             // 1 byte   - Canonical PDA bump
+}
+
+impl VoteReceipt {
+    // Calculate space: 8 (discriminator) + 32 + 32 + 8 + 1 = 81 bytes
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// A collection of one authority's counters, stored at the
+/// `[b"group", authority, group_id]` PDA
+#[account]
+#[derive(Default)]
+pub struct Group {
+    pub authority: Pubkey,    // 32 bytes - Owner of the group
+    pub group_id: u64,        // 8 bytes  - Distinguishes the authority's groups
+    pub bump: u8,             // 1 byte   - Canonical PDA bump
+}
+
+impl Group {
+    // Calculate space: 8 (discriminator) + 32 + 8 + 1 = 49 bytes
+    pub const SPACE: usize = 8 + 32 + 8 + 1;
+}
+
+/// Hash of a compressed counter's state, stored at the
+/// `[b"compressed", authority]` PDA until it is decompressed
+#[account]
+#[derive(Default)]
+pub struct CompressedCounter {
+    pub authority: Pubkey,        // 32 bytes - Owner of the compressed counter
+    pub state_hash: [u8; 32],     // 32 bytes - SHA-256 of the serialized counter
+    pub space: u64,               // 8 bytes  - Account size to recreate
+    pub bump: u8,                 // 1 byte   - Canonical PDA bump
+}
+
+impl CompressedCounter {
+    // Calculate space: 8 (discriminator) + 32 + 32 + 8 + 1 = 81 bytes
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+/// Rarely-changed display metadata for a counter, stored at the
+/// `[b"meta", counter]` PDA so editing it never rewrites the counter
+#[account]
+#[derive(Default)]
+pub struct CounterMeta {
+    pub counter: Pubkey,      // 32 bytes - Counter this metadata describes
+    pub label: String,        // 4 + 32 bytes - Human-readable name
+    pub note: String,         // 4 + 200 bytes - Freeform context
+    pub tags: Vec<String>,    // 4 bytes + (4 + len) per tag - Grown and shrunk with realloc
+    pub unit: Unit,           // 1 byte   - What the count measures
+    pub bump: u8,             // 1 byte   - Canonical PDA bump
+}
+
+impl CounterMeta {
+    // Calculate space: 8 (discriminator) + 32 + (4 + 32) + (4 + 200) + 4 + 1 + 1 = 286 bytes
+    // Tags start empty; each one adds its serialized size on top
+    pub const SPACE: usize = 8 + 32 + (4 + MAX_LABEL_LEN) + (4 + MAX_NOTE_LEN) + 4 + 1 + 1;
+
+    /// Bytes needed to hold `tags` on top of the base layout
+    pub fn space_for(tags: &[String]) -> usize {
+        Self::SPACE + tags.iter().map(|tag| 4 + tag.len()).sum::<usize>()
+    }
+}
+
+/// Return value of `get_scaled_count`; the display value is
+/// `count / 10^decimals`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ScaledCount {
+    pub count: u64,
+    pub decimals: u8,
+}
+
+/// Return value of `derive_pda`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DerivedPda {
+    pub address: Pubkey,
+    pub bump: u8,
+}
+
+/// Return value of `get_seen_range`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct SeenRange {
+    pub min_seen: u64,
+    pub max_seen: u64,
+}
+
+/// Return value of `get_global_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GlobalStats {
+    pub total_counters_created: u64,
+    pub total_increments: u64,
+    pub total_closed: u64,
+}
+
+/// Return value of `get_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CounterStats {
+    pub count: u64,
+    pub total_increments: u64,
+}
+
+/// Return value of `get_status`; `rate_limited` means an increment now
+/// would be refused by the minimum interval, token bucket, circuit breaker
+/// or daily cap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CounterStatus {
+    pub count: u64,
+    pub paused: bool,
+    pub archived: bool,
+    pub rate_limited: bool,
+    pub cap_reached: bool,
+}
+
+// ========================================
+// INSTRUCTION CONTEXTS
+// ========================================
+
+/// Context for initialize instruction
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(
+        init,                          // Create new account
+        payer = authority,             // Who pays for account creation
+        space = 8 + Counter::INIT_SPACE, // Account size in bytes
+        seeds = [b"counter", authority.key().as_ref()],
+        bump                           // Use canonical bump
+    )]
+    pub counter: Account<'info, Counter>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of defaults and the creation allowlist; required so the
+    /// allowlist cannot be skipped by leaving it out
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Group the new counter joins, if any
+    #[account(has_one = authority)]
+    pub group: Option<Account<'info, Group>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for initialize_with_bounty; the vault is the counter's
+/// `[b"bounty", counter]` PDA, created by the funding transfer
+#[derive(Accounts)]
+pub struct InitializeWithBounty<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Counter::INIT_SPACE,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        seeds = [b"bounty", counter.key().as_ref()],
+        bump
+    )]
+    pub bounty_vault: SystemAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of defaults and the creation allowlist
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for initialize_gated; creation requires holding the gate mint
+#[derive(Accounts)]
+pub struct InitializeGated<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Counter::INIT_SPACE,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Holds the gate mint and the defaults for the new counter
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: Mint, owner and balance are checked in the handler
+    #[account(owner = TOKEN_PROGRAM_ID)]
+    pub token_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for initialize_or_get; creates the counter PDA only when missing
+#[derive(Accounts)]
+pub struct InitializeOrGet<'info> {
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + Counter::INIT_SPACE,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of defaults and the creation allowlist
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for initialize_keypair; the counter address is a new keypair
+#[derive(Accounts)]
+pub struct InitializeKeypair<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Counter::INIT_SPACE
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of defaults and the creation allowlist
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Group the new counter joins, if any
+    #[account(has_one = authority)]
+    pub group: Option<Account<'info, Group>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for clone_counter; the copy lives under its own `b"clone"` prefix
+/// so it cannot collide with batch_initialize's indexed counters
+#[derive(Accounts)]
+#[instruction(new_seed: u8)]
+pub struct CloneCounter<'info> {
+    #[account(has_one = authority)]
+    pub source: Account<'info, Counter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Counter::LEN,
+        seeds = [b"clone", authority.key().as_ref(), &[new_seed]],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Checked for the creation allowlist
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for batch_initialize; the new counters are passed as remaining accounts
+#[derive(Accounts)]
+pub struct BatchInitialize<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Source of defaults and the creation allowlist
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for creating the global config
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(
+        init,
+        payer = admin,
+        space = Config::SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for updating the global config
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    #[account(
+        mut,
+        has_one = admin,
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub admin: Signer<'info>,
+}
+
+/// Context for initialize_signed_counter
+#[derive(Accounts)]
+pub struct InitializeSignedCounter<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = SignedCounter::SPACE,
+        seeds = [b"signed-counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, SignedCounter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for changing a signed counter's value
+#[derive(Accounts)]
+pub struct UpdateSignedCounter<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"signed-counter", authority.key().as_ref()],
+        bump = counter.bump
+    )]
+    pub counter: Account<'info, SignedCounter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+// Contexts below identify an existing counter through `has_one` plus
+// Anchor's owner and discriminator checks rather than re-deriving the PDA,
+// so keypair counters and counters whose authority has changed both work.
+
+/// Context for increment instruction
+#[derive(Accounts)]
+pub struct Increment<'info> {
+    #[account(
+        mut,                           // Account will be modified
+        constraint = counter.can_increment(&authority.key())
+            @ ErrorCode::Unauthorized, // Authority or delegate
+        constraint = !counter.requires_cosigner() || cosigner.is_some()
+            @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+    
+    /// The counter authority or its delegate; receives any bounty
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Second signer, required when the counter has a cosigner set
+    #[account(address = counter.cosigner @ ErrorCode::CosignerRequired)]
+    pub cosigner: Option<Signer<'info>>,
+
+    /// Holds the SOL paid out as `bounty_lamports` per increment
+    #[account(
+        mut,
+        seeds = [b"bounty", counter.key().as_ref()],
+        bump
+    )]
+    pub bounty_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_cpi; identical checks to `Increment` without the
+/// bounty accounts, so a PDA signer from a calling program is enough
+#[derive(Accounts)]
+pub struct IncrementCpi<'info> {
+    #[account(
+        mut,
+        constraint = counter.can_increment(&authority.key())
+            @ ErrorCode::Unauthorized,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// The counter authority or its delegate, possibly a PDA signer
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_priority; the config is required for its treasury
+#[derive(Accounts)]
+pub struct IncrementPriority<'info> {
+    #[account(
+        mut,
+        constraint = counter.can_increment(&authority.key())
+            @ ErrorCode::Unauthorized,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// The counter authority or its delegate; pays the tip
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        address = config.treasury
+    )]
+    pub treasury: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for increment_paid; recipients are passed as remaining accounts
+#[derive(Accounts)]
+pub struct IncrementPaid<'info> {
+    #[account(
+        mut,
+        constraint = counter.can_increment(&authority.key())
+            @ ErrorCode::Unauthorized,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// The counter authority or its delegate; pays the fee
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for open_vote_round instruction
+#[derive(Accounts)]
+pub struct OpenVoteRound<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for vote; any holder of the round's mint may vote
+#[derive(Accounts)]
+pub struct Vote<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+
+    /// Token holder casting the vote; pays for the receipt
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    /// CHECK: Mint, owner and balance are checked in the handler
+    #[account(owner = TOKEN_PROGRAM_ID)]
+    pub token_account: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = voter,
+        space = VoteReceipt::SPACE,
+        seeds = [b"vote", counter.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub receipt: Account<'info, VoteReceipt>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_with_password; the password replaces the
+/// authority check, so any signer may call it
+#[derive(Accounts)]
+pub struct IncrementWithPassword<'info> {
+    #[account(
+        mut,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub signer: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_if_other; `other` is only read
+#[derive(Accounts)]
+pub struct IncrementIfOther<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        has_one = authority,
+        constraint = other.key() != counter.key() @ ErrorCode::SameAccount
+    )]
+    pub other: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_with_bump
+///
+/// `authority` comes first so the canonical-bump check runs before the
+/// counter's seeds constraint.
+#[derive(Accounts)]
+#[instruction(bump: u8)]
+pub struct IncrementWithBump<'info> {
+    #[account(
+        constraint = Pubkey::find_program_address(
+            &[b"counter", authority.key().as_ref()],
+            &crate::ID
+        ).1 == bump @ ErrorCode::InvalidBump
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump = bump,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for stage_increment, finalize and cancel_pending
+#[derive(Accounts)]
+pub struct PendingIncrement<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_force; authority only
+#[derive(Accounts)]
+pub struct IncrementForce<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_random
+#[derive(Accounts)]
+pub struct IncrementRandom<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Address is constrained to the SlotHashes sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for schedule_increment instruction
+#[derive(Accounts)]
+pub struct ScheduleIncrement<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for crank; `cranker` need not be the authority
+#[derive(Accounts)]
+pub struct Crank<'info> {
+    #[account(mut)]
+    pub counter: Account<'info, Counter>,
+
+    pub cranker: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for read_and_count; `reader` need not be the authority
+#[derive(Accounts)]
+pub struct ReadAndCount<'info> {
+    #[account(
+        mut,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub reader: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_pda_authority; the authority signs via
+/// `invoke_signed` from the program that owns it
+#[derive(Accounts)]
+pub struct IncrementPdaAuthority<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_with_proof
+#[derive(Accounts)]
+pub struct IncrementWithProof<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_signed; authorization comes from the Ed25519 check
+#[derive(Accounts)]
+pub struct IncrementSigned<'info> {
+    #[account(
+        mut,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// CHECK: Address is constrained to the instructions sysvar
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for batch increment; counters are passed as remaining accounts
+#[derive(Accounts)]
+pub struct BatchIncrement<'info> {
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for batch_reset; counters are passed as remaining accounts
+#[derive(Accounts)]
+pub struct BatchReset<'info> {
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for decrement instruction
+#[derive(Accounts)]
+pub struct Decrement<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+    
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_mint_reward
+#[derive(Accounts)]
+pub struct IncrementMintReward<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: PDA that signs as the reward mint authority; holds no data
+    #[account(seeds = [b"reward-authority", counter.key().as_ref()], bump)]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Reward mint; the token program checks its authority
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Signer's token account for `mint`; checked in the handler
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Address is constrained to the SPL Token program
+    #[account(address = TOKEN_PROGRAM_ID)]
+    pub token_program: UncheckedAccount<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for decrement_with_burn
+#[derive(Accounts)]
+pub struct DecrementWithBurn<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// CHECK: Token account to burn from; validated by the token program
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Mint of `token_account`; validated by the token program
+    #[account(mut)]
+    pub mint: UncheckedAccount<'info>,
+
+    /// CHECK: Address is constrained to the SPL Token program
+    #[account(address = TOKEN_PROGRAM_ID)]
+    pub token_program: UncheckedAccount<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for reset instructions
+#[derive(Accounts)]
+pub struct Reset<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for swapping the values of two counters
+#[derive(Accounts)]
+pub struct Swap<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter_a: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = counter_b.key() != counter_a.key() @ ErrorCode::SameAccount
+    )]
+    pub counter_b: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for saving and restoring checkpoints
+#[derive(Accounts)]
+pub struct Checkpoint<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for close instruction
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority              // Refund rent to the authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Closed along with the counter when passed
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        close = authority
+    )]
+    pub meta: Option<Account<'info, CounterMeta>>,
+
+    /// The closed counter is removed from this registry when passed; an authority that took
+    /// the counter over by transfer may have none
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, Registry>>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Must be empty so backers' deposits are not stranded with the counter
+    #[account(
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump,
+        constraint = escrow_vault.lamports() == 0 @ ErrorCode::EscrowNotEmpty
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+}
+
+/// Context for merge; both counters must share the signing authority
+#[derive(Accounts)]
+pub struct Merge<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub destination: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = source.key() != destination.key() @ ErrorCode::SameAccount,
+        close = authority
+    )]
+    pub source: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The source's metadata, closed along with it when passed
+    #[account(
+        mut,
+        seeds = [b"meta", source.key().as_ref()],
+        bump = source_meta.bump,
+        close = authority
+    )]
+    pub source_meta: Option<Account<'info, CounterMeta>>,
+
+    /// The source must not hold escrowed funds, which would be stranded
+    #[account(
+        seeds = [b"escrow", source.key().as_ref()],
+        bump,
+        constraint = escrow_vault.lamports() == 0 @ ErrorCode::EscrowNotEmpty
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+
+    /// The merged source is removed from this registry when passed; an authority that took
+    /// the counter over by transfer may have none
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, Registry>>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_expires_after instruction
+#[derive(Accounts)]
+pub struct SetExpiresAfter<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for extend instruction
+#[derive(Accounts)]
+pub struct Extend<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for repair_timestamp instruction
+#[derive(Accounts)]
+pub struct RepairTimestamp<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_count_on_read instruction
+#[derive(Accounts)]
+pub struct SetCountOnRead<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for close_expired; `closer` need not be the authority
+#[derive(Accounts)]
+pub struct CloseExpired<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        close = authority              // Refund rent to the authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// CHECK: Only receives the rent refund; checked against the counter
+    #[account(mut)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub closer: Signer<'info>,
+
+    /// Closed along with the counter when passed
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        close = authority
+    )]
+    pub meta: Option<Account<'info, CounterMeta>>,
+
+    /// The closed counter is removed from this registry when passed; an authority that took
+    /// the counter over by transfer may have none
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, Registry>>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    /// Must be empty so backers' deposits are not stranded with the counter
+    #[account(
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump,
+        constraint = escrow_vault.lamports() == 0 @ ErrorCode::EscrowNotEmpty
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+}
+
+/// Context for sweep_excess instruction
+#[derive(Accounts)]
+pub struct SweepExcess<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for compress; the counter must be the authority's default PDA
+#[derive(Accounts)]
+pub struct Compress<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump,
+        close = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = CompressedCounter::SPACE,
+        seeds = [b"compressed", authority.key().as_ref()],
+        bump
+    )]
+    pub compressed: Account<'info, CompressedCounter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Closed along with the counter when passed
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        close = authority
+    )]
+    pub meta: Option<Account<'info, CounterMeta>>,
+
+    /// Must be empty; the vault is tied to the address being closed
+    #[account(
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump,
+        constraint = escrow_vault.lamports() == 0 @ ErrorCode::EscrowNotEmpty
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+
+    /// The compressed counter is removed from this registry when passed; an authority that took
+    /// the counter over by transfer may have none
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, Registry>>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for decompress; the counter is recreated at its original PDA
+#[derive(Accounts)]
+pub struct Decompress<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = compressed.space as usize,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump
+    )]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"compressed", authority.key().as_ref()],
+        bump = compressed.bump,
+        close = authority
+    )]
+    pub compressed: Account<'info, CompressedCounter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// The authority's registry of counters, created on first use
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = Registry::SPACE,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump
+    )]
+    pub registry: Account<'info, Registry>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for migrate_seed; both addresses are derived from the authority
+#[derive(Accounts)]
+#[instruction(new_seed_suffix: String)]
+pub struct MigrateSeed<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        seeds = [b"counter", authority.key().as_ref()],
+        bump,
+        close = authority
+    )]
+    pub old_counter: Account<'info, Counter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Counter::LEN.max(old_counter.to_account_info().data_len()),
+        seeds = [b"counter", new_seed_suffix.as_bytes(), authority.key().as_ref()],
+        bump
+    )]
+    pub new_counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Copied to `new_meta` and closed when passed
+    #[account(
+        mut,
+        seeds = [b"meta", old_counter.key().as_ref()],
+        bump = old_meta.bump,
+        close = authority
+    )]
+    pub old_meta: Option<Account<'info, CounterMeta>>,
+
+    /// CHECK: Created in the handler when `old_meta` is passed
+    #[account(
+        mut,
+        seeds = [b"meta", new_counter.key().as_ref()],
+        bump
+    )]
+    pub new_meta: Option<UncheckedAccount<'info>>,
+
+    /// Must be empty; the vault is derived from the old address and would not follow the move
+    #[account(
+        seeds = [b"escrow", old_counter.key().as_ref()],
+        bump,
+        constraint = escrow_vault.lamports() == 0 @ ErrorCode::EscrowNotEmpty
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+
+    /// The old address is replaced by the new one in this registry when passed; an authority that took
+    /// the counter over by transfer may have none
+    #[account(
+        mut,
+        seeds = [b"registry", authority.key().as_ref()],
+        bump = registry.bump
+    )]
+    pub registry: Option<Account<'info, Registry>>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for migrate instruction
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    /// CHECK: Older counters are too small to deserialize as `Counter`, so the
+    /// discriminator and authority are checked in the handler instead.
+    #[account(
+        mut,
+        owner = crate::ID
+    )]
+    pub counter: UncheckedAccount<'info>,
+
+    /// CHECK: Created in the handler, only when an older counter's inline
+    /// metadata moves out and the account does not exist yet
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump
+    )]
+    pub meta: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for configuring co-owners; approvals are passed as remaining accounts
+#[derive(Accounts)]
+pub struct SetAuthorities<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for proposing or cancelling an authority transfer
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_transfer_timelock instruction
+#[derive(Accounts)]
+pub struct SetTransferTimelock<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for accepting a proposed authority transfer
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        constraint = counter.pending_authority == new_authority.key()
+            @ ErrorCode::NotPendingAuthority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub new_authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for configure instruction
+#[derive(Accounts)]
+pub struct Configure<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    /// Only needed when the label changes
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter
+    )]
+    pub meta: Option<Account<'info, CounterMeta>>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_private, grant_reader and revoke_reader
+#[derive(Accounts)]
+pub struct ManageReaders<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_cosigner instruction
+#[derive(Accounts)]
+pub struct SetCosigner<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_milestones instruction
+#[derive(Accounts)]
+pub struct SetMilestones<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_token_bucket instruction
+#[derive(Accounts)]
+pub struct SetTokenBucket<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_daily_cap instruction
+#[derive(Accounts)]
+pub struct SetDailyCap<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_circuit_breaker instruction
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for initialize_meta instruction
+#[derive(Accounts)]
+pub struct InitializeMeta<'info> {
+    #[account(has_one = authority)]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = CounterMeta::SPACE,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump
+    )]
+    pub meta: Account<'info, CounterMeta>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for the metadata setters that keep the account's size; the
+/// counter is only read to check the authority
+#[derive(Accounts)]
+pub struct UpdateMeta<'info> {
+    #[account(has_one = authority)]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter
+    )]
+    pub meta: Account<'info, CounterMeta>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for add_tag; grows the metadata account by the tag's serialized size
+#[derive(Accounts)]
+#[instruction(tag: String)]
+pub struct AddTag<'info> {
+    #[account(has_one = authority)]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter,
+        realloc = meta.to_account_info().data_len() + 4 + tag.len(),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub meta: Account<'info, CounterMeta>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for remove_tag; shrinks the metadata account by the tag's serialized size
+#[derive(Accounts)]
+#[instruction(tag: String)]
+pub struct RemoveTag<'info> {
+    #[account(has_one = authority)]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter,
+        realloc = meta.to_account_info().data_len().saturating_sub(4 + tag.len()),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub meta: Account<'info, CounterMeta>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for set_meta_tags; resizes the metadata account to fit the new list
+#[derive(Accounts)]
+#[instruction(tags: Vec<String>)]
+pub struct SetMetaTags<'info> {
+    #[account(has_one = authority)]
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        mut,
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter,
+        realloc = CounterMeta::space_for(&tags),
+        realloc::payer = authority,
+        realloc::zero = false
+    )]
+    pub meta: Account<'info, CounterMeta>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for configuring the per-increment bounty
+#[derive(Accounts)]
+pub struct SetBounty<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for funding a counter's bounty vault
+#[derive(Accounts)]
+pub struct FundBounty<'info> {
+    pub counter: Account<'info, Counter>,
 
-    /// Increment the counter
-    pub fn increment(ctx: Context<Increment>) -> Result<()> {
-        let counter = &mut ctx.accounts.counter;
-        
-        // Overflow protection
-        counter.count = counter.count.checked_add(1)
-            .ok_or(ErrorCode::Overflow)?;
-        
-        counter.last_updated = Clock::get()?.unix_timestamp;
-        
-        msg!("Counter incremented to: {}", counter.count);
-        Ok(())
-    }
+    #[account(mut)]
+    pub funder: Signer<'info>,
 
-    /// Transfer counter authority (ownership)
-    pub fn transfer_authority(
-        ctx: Context<TransferAuthority>, 
-        new_authority: Pubkey
-    ) -> Result<()> {
-        let counter = &mut ctx.accounts.counter;
-        counter.authority = new_authority;
-        
-        msg!("Authority transferred to: {}", new_authority);
-        Ok(())
-    }
+    #[account(
+        mut,
+        seeds = [b"bounty", counter.key().as_ref()],
+        bump
+    )]
+    pub bounty_vault: SystemAccount<'info>,
 
-    /// Get current counter value (view function)
-    pub fn get_count(ctx: Context<GetCount>) -> Result<u64> {
-        Ok(ctx.accounts.counter.count)
-    }
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
 }
 
-// ========================================
-// ACCOUNT STRUCTURES
-// ========================================
+/// Context for set_escrow_target instruction
+#[derive(Accounts)]
+pub struct SetEscrowTarget<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
 
-/// Counter account structure
-#[account]
-#[derive(Default)]
-pub struct Counter {
-    pub authority: Pubkey,    // 32 bytes - Who can modify this counter
-    pub count: u64,           // 8 bytes  - Current count value
-    pub last_updated: i64,    // 8 bytes  - Unix timestamp of last update
-}
+    pub authority: Signer<'info>,
 
-// Calculate space: 8 (discriminator) + 32 + 8 + 8 = 56 bytes
+    #[account(
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: SystemAccount<'info>,
 
-// ========================================
-// INSTRUCTION CONTEXTS
-// ========================================
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
 
-/// Context for initialize instruction
+/// Context for funding a counter's escrow vault
 #[derive(Accounts)]
-pub struct Initialize<'info> {
+pub struct FundEscrow<'info> {
+    pub counter: Account<'info, Counter>,
+
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
     #[account(
-        init,                          // Create new account
-        payer = authority,             // Who pays for account creation
-        space = 8 + 32 + 8 + 8,       // Account size in bytes
-        seeds = [b"counter", authority.key().as_ref()],
-        bump                           // Use canonical bump
+        mut,
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
     )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for claim_escrow; only the authority receives the funds
+#[derive(Accounts)]
+pub struct ClaimEscrow<'info> {
+    #[account(has_one = authority)]
     pub counter: Account<'info, Counter>,
-    
+
     #[account(mut)]
     pub authority: Signer<'info>,
-    
+
+    #[account(
+        mut,
+        seeds = [b"escrow", counter.key().as_ref()],
+        bump
+    )]
+    pub escrow_vault: SystemAccount<'info>,
+
     pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
 }
 
-/// Context for increment instruction
+/// Context for setting or revoking the delegate and editing the delegates list
 #[derive(Accounts)]
-pub struct Increment<'info> {
+pub struct SetDelegate<'info> {
     #[account(
-        mut,                           // Account will be modified
-        has_one = authority,           // Verify authority matches
-        seeds = [b"counter", authority.key().as_ref()],
-        bump
+        mut,
+        has_one = authority
     )]
     pub counter: Account<'info, Counter>,
-    
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for rotating the freeze authority
+#[derive(Accounts)]
+pub struct SetFreezeAuthority<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for archive and unarchive
+#[derive(Accounts)]
+pub struct SetArchived<'info> {
+    #[account(
+        mut,
+        has_one = authority
+    )]
+    pub counter: Account<'info, Counter>,
+
     pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for pause and unpause; only the freeze authority may sign
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(
+        mut,
+        has_one = freeze_authority
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub freeze_authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
 }
 
 /// Context for transfer authority
@@ -106,19 +6229,307 @@ pub struct Increment<'info> {
 pub struct TransferAuthority<'info> {
     #[account(
         mut,
-        has_one = authority,
-        seeds = [b"counter", authority.key().as_ref()],
-        bump
+        has_one = authority
     )]
     pub counter: Account<'info, Counter>,
     
     pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for increment_and_transfer
+#[derive(Accounts)]
+pub struct IncrementAndTransfer<'info> {
+    #[account(
+        mut,
+        has_one = authority,
+        constraint = !counter.requires_cosigner() @ ErrorCode::CosignerRequired
+    )]
+    pub counter: Account<'info, Counter>,
+
+    pub authority: Signer<'info>,
+
+    /// Global kill switch and program-wide stats
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for batch_transfer_authority; counters are passed as remaining accounts
+#[derive(Accounts)]
+pub struct BatchTransferAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for reading the global config (no signer required)
+#[derive(Accounts)]
+pub struct GetGlobalStats<'info> {
+    #[account(seeds = [b"config"], bump = config.bump)]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for reading a counter's metadata (no signer required)
+#[derive(Accounts)]
+pub struct GetMeta<'info> {
+    pub counter: Account<'info, Counter>,
+
+    #[account(
+        seeds = [b"meta", counter.key().as_ref()],
+        bump = meta.bump,
+        has_one = counter
+    )]
+    pub meta: Account<'info, CounterMeta>,
+}
+
+/// Context for get_status (no signer required)
+#[derive(Accounts)]
+pub struct GetStatus<'info> {
+    pub counter: Account<'info, Counter>,
+
+    /// Only read for the global pause
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump
+    )]
+    pub config: Option<Account<'info, Config>>,
 }
 
 /// Context for reading counter (no signer required)
 #[derive(Accounts)]
 pub struct GetCount<'info> {
     pub counter: Account<'info, Counter>,
+
+    /// Only needed by `get_count` on a private counter
+    pub reader: Option<Signer<'info>>,
+}
+
+/// Context for get_ratio; both accounts must be counters of this program
+#[derive(Accounts)]
+pub struct GetRatio<'info> {
+    pub numerator: Account<'info, Counter>,
+
+    pub denominator: Account<'info, Counter>,
+}
+
+/// Context for get_count_raw
+#[derive(Accounts)]
+pub struct GetCountRaw<'info> {
+    /// CHECK: Deliberately not deserialized as `Counter`; the handler runs
+    /// `validate_counter` and reads the count by offset.
+    pub counter: UncheckedAccount<'info>,
+}
+
+/// Context for initialize_group instruction
+#[derive(Accounts)]
+#[instruction(group_id: u64)]
+pub struct InitializeGroup<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = Group::SPACE,
+        seeds = [b"group", authority.key().as_ref(), &group_id.to_le_bytes()],
+        bump
+    )]
+    pub group: Account<'info, Group>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Global kill switch; mutations fail while the config is paused
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = !config.global_pause @ ErrorCode::GloballyPaused
+    )]
+    pub config: Account<'info, Config>,
+}
+
+/// Context for group_sum; the group's counters are passed as remaining accounts
+#[derive(Accounts)]
+pub struct GroupSum<'info> {
+    pub group: Account<'info, Group>,
+}
+
+/// Context for reading counters passed as remaining accounts; the system
+/// program is only here because an accounts struct needs at least one field
+#[derive(Accounts)]
+pub struct SumCounters<'info> {
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for derive_pda; the derivation reads no accounts, and the system
+/// program is only here because an accounts struct needs at least one field
+#[derive(Accounts)]
+pub struct DerivePda<'info> {
+    pub system_program: Program<'info, System>,
+}
+
+// ========================================
+// EVENTS
+// ========================================
+
+/// Emitted when a counter is created
+#[event]
+pub struct CounterInitialized {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a counter value goes up
+#[event]
+pub struct CounterIncremented {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `emit_leaf` with the counter's current state leaf
+#[event]
+pub struct CounterLeaf {
+    pub counter: Pubkey,
+    pub leaf: [u8; 32],
+    pub slot: u64,
+}
+
+/// Emitted when a wrapping counter rolls over past its cap
+#[event]
+pub struct CounterWrapped {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted by `increment_with_memo` with the attached memo
+#[event]
+pub struct IncrementMemo {
+    pub counter: Pubkey,
+    pub signer: Pubkey,
+    pub count: u64,
+    pub memo: String,
+}
+
+/// Emitted when a counter value goes down
+#[event]
+pub struct CounterDecremented {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when a counter is reset to a starting value
+#[event]
+pub struct CounterReset {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub previous_count: u64,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted once for a whole `apply_deltas` list
+#[event]
+pub struct DeltasApplied {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub previous_count: u64,
+    pub count: u64,
+    pub deltas_applied: u32,
+    pub timestamp: i64,
+}
+
+/// Emitted by `increment` the first time the count reaches a milestone
+#[event]
+pub struct MilestoneReached {
+    pub counter: Pubkey,
+    pub milestone: u64,
+    pub count: u64,
+    pub timestamp: i64,
+}
+
+/// Emitted when two counters exchange values; counts are after the swap
+#[event]
+pub struct CountersSwapped {
+    pub counter_a: Pubkey,
+    pub counter_b: Pubkey,
+    pub count_a: u64,
+    pub count_b: u64,
+    pub timestamp: i64,
+}
+
+/// A counter and its count, as listed in `BatchIncremented`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CounterValue {
+    pub counter: Pubkey,
+    pub count: u64,
+}
+
+/// Emitted once per `batch_increment` with every counter's new count
+#[event]
+pub struct BatchIncremented {
+    pub authority: Pubkey,
+    pub entries: Vec<CounterValue>,
+    pub timestamp: i64,
+}
+
+/// Emitted when a counter is compressed; `state` is its serialized data
+#[event]
+pub struct CounterCompressed {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub state: Vec<u8>,
+    pub timestamp: i64,
+}
+
+/// Emitted when a counter account is closed
+#[event]
+pub struct CounterClosed {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when a new authority is proposed
+#[event]
+pub struct AuthorityProposed {
+    pub counter: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub timestamp: i64,
+}
+
+/// Emitted when counter ownership changes hands
+#[event]
+pub struct AuthorityTransferred {
+    pub counter: Pubkey,
+    pub previous_authority: Pubkey,
+    pub new_authority: Pubkey,
+    pub timestamp: i64,
 }
 
 // ========================================
@@ -127,6 +6538,166 @@ pub struct GetCount<'info> {
 
 #[error_code]
 pub enum ErrorCode {
-    #[msg("Counter overflow")]
-    Overflow,
+    #[msg("Counter overflow on addition")]
+    AddOverflow,
+    #[msg("Counter underflow")]
+    Underflow,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Counter has reached its maximum value")]
+    MaxValueReached,
+    #[msg("Counter is paused")]
+    CounterPaused,
+    #[msg("Signer is not the pending authority")]
+    NotPendingAuthority,
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    #[msg("Counter was updated too recently")]
+    RateLimited,
+    #[msg("Signer is not authorized for this counter")]
+    Unauthorized,
+    #[msg("Counter account must be writable")]
+    AccountNotWritable,
+    #[msg("Label exceeds the maximum length")]
+    LabelTooLong,
+    #[msg("Counter has reached its minimum value")]
+    MinValueReached,
+    #[msg("Minimum value must not exceed maximum value")]
+    InvalidBounds,
+    #[msg("Not enough co-owners signed")]
+    InsufficientSigners,
+    #[msg("Threshold exceeds the number of co-owners")]
+    InvalidThreshold,
+    #[msg("Memo exceeds the maximum length")]
+    MemoTooLong,
+    #[msg("Expected an Ed25519 signature instruction before this one")]
+    MissingSignatureInstruction,
+    #[msg("Ed25519 instruction does not match the expected signer or message")]
+    InvalidSignatureInstruction,
+    #[msg("Signed message must be the counter address and current nonce")]
+    InvalidSignedMessage,
+    #[msg("Counter version does not match the expected version")]
+    VersionMismatch,
+    #[msg("Counter value does not match the expected value")]
+    UnexpectedValue,
+    #[msg("Too many deltas in one instruction")]
+    TooManyDeltas,
+    #[msg("All counters are paused by the program admin")]
+    GloballyPaused,
+    #[msg("Authority registry is full")]
+    RegistryFull,
+    #[msg("Number of accounts passed does not match the requested count")]
+    AccountCountMismatch,
+    #[msg("Counter account does not match its expected address")]
+    InvalidCounterAddress,
+    #[msg("Bounty vault cannot cover the bounty")]
+    BountyExhausted,
+    #[msg("Counter is archived")]
+    CounterArchived,
+    #[msg("Insufficient lamports for the requested transfer")]
+    InsufficientFunds,
+    #[msg("New authority must not be the default pubkey")]
+    InvalidAuthority,
+    #[msg("New authority is already the current authority")]
+    SameAuthority,
+    #[msg("Authority does not hold the required token")]
+    NotTokenHolder,
+    #[msg("Note exceeds the maximum length")]
+    NoteTooLong,
+    #[msg("The two counter accounts must be different")]
+    SameAccount,
+    #[msg("Fee split shares must sum to 10000 basis points")]
+    InvalidSplit,
+    #[msg("Account does not match the configured fee recipient")]
+    InvalidFeeRecipient,
+    #[msg("Authority is not on the config allowlist")]
+    NotAllowlisted,
+    #[msg("Config allowlist is full")]
+    AllowlistFull,
+    #[msg("Counter requires its cosigner to sign")]
+    CosignerRequired,
+    #[msg("Change exceeds the counter's maximum step")]
+    ChangeTooLarge,
+    #[msg("Unlock time has not been reached")]
+    TooEarly,
+    #[msg("No voting round is open")]
+    VotingClosed,
+    #[msg("Voter has already voted in this round")]
+    AlreadyVoted,
+    #[msg("History index is out of range")]
+    IndexOutOfRange,
+    #[msg("Tag exceeds the maximum length")]
+    TagTooLong,
+    #[msg("Counter has the maximum number of tags")]
+    TooManyTags,
+    #[msg("Counter already has this tag")]
+    DuplicateTag,
+    #[msg("Counter does not have this tag")]
+    TagNotFound,
+    #[msg("Transaction landed after its deadline")]
+    DeadlineExceeded,
+    #[msg("Password does not match")]
+    WrongPassword,
+    #[msg("State does not match the compressed hash")]
+    CompressedStateMismatch,
+    #[msg("The other counter has not reached the threshold")]
+    ConditionNotMet,
+    #[msg("Too many counters passed")]
+    TooManyCounters,
+    #[msg("Counter has reached its authority transfer limit")]
+    TransferLimitReached,
+    #[msg("Odds must be at least 1 in 1")]
+    InvalidOdds,
+    #[msg("Counter overflow on multiplication")]
+    MulOverflow,
+    #[msg("Denominator counter is zero")]
+    DivideByZero,
+    #[msg("Counter has not been idle long enough to expire")]
+    NotExpired,
+    #[msg("Bump is not the counter's canonical bump")]
+    InvalidBump,
+    #[msg("Account would fall below its rent-exempt minimum")]
+    WouldBeNonRentExempt,
+    #[msg("Counter does not belong to this group")]
+    NotInGroup,
+    #[msg("Batch exceeds MAX_BATCH counters")]
+    BatchTooLarge,
+    #[msg("Too many increments in a short window; try again after the cooldown")]
+    CircuitBreakerTripped,
+    #[msg("Signer may not read this private counter")]
+    NotAuthorizedReader,
+    #[msg("Reader list is full")]
+    ReadersFull,
+    #[msg("An increment is already pending")]
+    PendingExists,
+    #[msg("No increment is pending")]
+    NoPendingIncrement,
+    #[msg("Transaction is missing the required memo instruction")]
+    MissingRequiredInstruction,
+    #[msg("Count has not reached the escrow target")]
+    TargetNotReached,
+    #[msg("Escrow target cannot change while the vault holds funds")]
+    EscrowFunded,
+    #[msg("Escrow target must be set before funding")]
+    EscrowTargetNotSet,
+    #[msg("last_updated is already valid")]
+    NothingToRepair,
+    #[msg("Counter is locked by an instruction already in progress")]
+    Reentrancy,
+    #[msg("Incremented value would exceed the caller's bound")]
+    ExceedsMaxAcceptable,
+    #[msg("Authority is not the PDA derived from the given seeds and program")]
+    InvalidPdaAuthority,
+    #[msg("Daily increment cap reached")]
+    DailyCapReached,
+    #[msg("Authority transfer timelock has not elapsed")]
+    TimelockActive,
+    #[msg("Scheduled increment queue is full")]
+    QueueFull,
+    #[msg("Delegates list is full")]
+    DelegateLimitReached,
+    #[msg("Counter metadata account is required for this update")]
+    MissingMeta,
+    #[msg("Escrow vault still holds funds")]
+    EscrowNotEmpty,
 }
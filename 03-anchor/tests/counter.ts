@@ -1,7 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { Counter } from "../target/types/counter";
+import { CounterCaller } from "../target/types/counter_caller";
 import { expect } from "chai";
+import { createHash } from "crypto";
+import * as fs from "fs";
 
 describe("counter", () => {
   // Configure the client to use the local cluster
@@ -10,11 +13,158 @@ describe("counter", () => {
   const program = anchor.workspace.Counter as Program<Counter>;
   const provider = anchor.getProvider();
 
+  // Initialize parameters with every optional setting left unset
+  const defaultParams = () => ({
+    maxValue: null,
+    minIntervalSeconds: null,
+    minValue: null,
+    wrap: null,
+    freezeAuthority: null,
+    decayPerSecond: null,
+    cosigner: null,
+    maxChangeBps: null,
+    overflowPolicy: null,
+    passwordHash: null,
+    maxTransfers: null,
+    decimals: null,
+  });
+
+  // Simulate a (possibly mutating) method call and return its raw return
+  // data; `.view()` only supports instructions without writable accounts
+  const simulateReturnData = async (method: {
+    simulate: () => Promise<{ raw: readonly string[] }>;
+  }) => {
+    const { raw } = await method.simulate();
+    const prefix = `Program return: ${program.programId} `;
+    const line = raw.find((log) => log.startsWith(prefix));
+    return Buffer.from(line.slice(prefix.length), "base64");
+  };
+
+  // Fetch a confirmed transaction and decode the Anchor events in its logs
+  const fetchEvents = async (signature: string) => {
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    return Array.from(parser.parseLogs(tx.meta.logMessages));
+  };
+
+  // Minimal SPL Token helpers built from raw instructions
+  const TOKEN_PROGRAM_ID = new anchor.web3.PublicKey(
+    "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+  );
+
+  // Create a zero-decimal mint controlled by `mintAuthority`
+  const createMint = async (mintAuthority: anchor.web3.PublicKey) => {
+    const mint = anchor.web3.Keypair.generate();
+    const space = 82;
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: provider.publicKey,
+        newAccountPubkey: mint.publicKey,
+        lamports:
+          await provider.connection.getMinimumBalanceForRentExemption(space),
+        space,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [{ pubkey: mint.publicKey, isSigner: false, isWritable: true }],
+        // InitializeMint2: decimals 0, no freeze authority
+        data: Buffer.concat([
+          Buffer.from([20, 0]),
+          mintAuthority.toBuffer(),
+          Buffer.from([0]),
+        ]),
+      })
+    );
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+  };
+
+  // Create a token account for `mint` owned by `owner`
+  const createTokenAccount = async (
+    mint: anchor.web3.PublicKey,
+    owner: anchor.web3.PublicKey
+  ) => {
+    const account = anchor.web3.Keypair.generate();
+    const space = 165;
+    const tx = new anchor.web3.Transaction().add(
+      anchor.web3.SystemProgram.createAccount({
+        fromPubkey: provider.publicKey,
+        newAccountPubkey: account.publicKey,
+        lamports:
+          await provider.connection.getMinimumBalanceForRentExemption(space),
+        space,
+        programId: TOKEN_PROGRAM_ID,
+      }),
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [
+          { pubkey: account.publicKey, isSigner: false, isWritable: true },
+          { pubkey: mint, isSigner: false, isWritable: false },
+        ],
+        // InitializeAccount3
+        data: Buffer.concat([Buffer.from([18]), owner.toBuffer()]),
+      })
+    );
+    await provider.sendAndConfirm(tx, [account]);
+    return account.publicKey;
+  };
+
+  // Mint `amount` tokens to `destination`
+  const mintTo = async (
+    mint: anchor.web3.PublicKey,
+    destination: anchor.web3.PublicKey,
+    mintAuthority: anchor.web3.Keypair,
+    amount: number
+  ) => {
+    const data = Buffer.concat([
+      Buffer.from([7]), // MintTo
+      new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+    ]);
+    const tx = new anchor.web3.Transaction().add(
+      new anchor.web3.TransactionInstruction({
+        programId: TOKEN_PROGRAM_ID,
+        keys: [
+          { pubkey: mint, isSigner: false, isWritable: true },
+          { pubkey: destination, isSigner: false, isWritable: true },
+          {
+            pubkey: mintAuthority.publicKey,
+            isSigner: true,
+            isWritable: false,
+          },
+        ],
+        data,
+      })
+    );
+    await provider.sendAndConfirm(tx, [mintAuthority]);
+  };
+
   // Test accounts
   let counterKeypair: anchor.web3.Keypair;
   let authorityKeypair: anchor.web3.Keypair;
   let newAuthorityKeypair: anchor.web3.Keypair;
 
+  // Every creation path requires the config, so create it up front with
+  // defaults that leave new counters unconstrained
+  before(async () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    if (!(await program.account.config.fetchNullable(configPda))) {
+      await program.methods
+        .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+    }
+  });
+
   beforeEach(async () => {
     // Generate fresh keypairs for each test
     counterKeypair = anchor.web3.Keypair.generate();
@@ -50,7 +200,7 @@ describe("counter", () => {
       );
 
       const tx = await program.methods
-        .initialize(initialValue)
+        .initialize(initialValue, defaultParams())
         .accounts({
           counter: counterPda,
           authority: authorityKeypair.publicKey,
@@ -89,7 +239,7 @@ describe("counter", () => {
       );
 
       await program.methods
-        .initialize(initialValue)
+        .initialize(initialValue, defaultParams())
         .accounts({
           counter: counterPda,
           authority: testAuthority.publicKey,
@@ -114,7 +264,7 @@ describe("counter", () => {
       );
 
       await program.methods
-        .initialize(new anchor.BN(0))
+        .initialize(new anchor.BN(0), defaultParams())
         .accounts({
           counter: counterPda,
           authority: authorityKeypair.publicKey,
@@ -180,7 +330,68 @@ describe("counter", () => {
         // Should not reach here
         expect.fail("Expected transaction to fail");
       } catch (error) {
-        expect(error.message).to.include("has_one");
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("Decrement Counter", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(1), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should decrement the counter", async () => {
+      await program.methods
+        .decrement()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+
+    it("Should fail to decrement below zero", async () => {
+      await program.methods
+        .decrement()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .decrement()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected underflow error");
+      } catch (error) {
+        expect(error.message).to.include("Underflow");
       }
     });
   });
@@ -195,7 +406,7 @@ describe("counter", () => {
       );
 
       await program.methods
-        .initialize(new anchor.BN(10))
+        .initialize(new anchor.BN(10), defaultParams())
         .accounts({
           counter: counterPda,
           authority: authorityKeypair.publicKey,
@@ -272,8 +483,47 @@ describe("counter", () => {
 
         expect.fail("Expected transaction to fail");
       } catch (error) {
-        expect(error.message).to.include("has_one");
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("Should reject a transfer to the default pubkey", async () => {
+      try {
+        await program.methods
+          .transferAuthority(anchor.web3.PublicKey.default)
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("InvalidAuthority");
+      }
+    });
+
+    it("Should reject a transfer to the current authority", async () => {
+      try {
+        await program.methods
+          .transferAuthority(authorityKeypair.publicKey)
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("SameAuthority");
       }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
     });
   });
 
@@ -287,7 +537,7 @@ describe("counter", () => {
       );
 
       await program.methods
-        .initialize(new anchor.BN(100))
+        .initialize(new anchor.BN(100), defaultParams())
         .accounts({
           counter: counterPda,
           authority: authorityKeypair.publicKey,
@@ -344,7 +594,7 @@ describe("counter", () => {
       const largeNumber = new anchor.BN("18446744073709551614"); // u64::MAX - 1
 
       await program.methods
-        .initialize(largeNumber)
+        .initialize(largeNumber, defaultParams())
         .accounts({
           counter: counterPda,
           authority: authorityKeypair.publicKey,
@@ -380,4 +630,7856 @@ describe("counter", () => {
       }
     });
   });
+
+  describe("Step Size", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should add 100 then subtract 50", async () => {
+      await program.methods
+        .incrementBy(new anchor.BN(100))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .decrementBy(new anchor.BN(50))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("50");
+    });
+
+    it("Should reject a zero amount", async () => {
+      try {
+        await program.methods
+          .incrementBy(new anchor.BN(0))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected invalid amount error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidAmount");
+      }
+    });
+  });
+
+  describe("Reset Counter", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(25), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should reset the counter to zero and refresh last_updated", async () => {
+      const before = await program.account.counter.fetch(counterPda);
+
+      // Wait so the clock moves past the initialize timestamp
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await program.methods
+        .reset()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+      expect(counterAccount.lastUpdated.toNumber()).to.be.greaterThan(
+        before.lastUpdated.toNumber()
+      );
+    });
+
+    it("Should reset the counter to an arbitrary value", async () => {
+      await program.methods
+        .resetTo(new anchor.BN(7))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("7");
+    });
+
+    it("Should reject a reset value above max_value", async () => {
+      await program.methods
+        .configure({
+          maxValue: new anchor.BN(30),
+          minIntervalSeconds: null,
+          label: null,
+        })
+        .accounts({
+          counter: counterPda,
+          meta: null,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .resetTo(new anchor.BN(31))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected max value error");
+      } catch (error) {
+        expect(error.message).to.include("MaxValueReached");
+      }
+    });
+
+    it("Should fail when wrong authority tries to reset", async () => {
+      try {
+        await program.methods
+          .reset()
+          .accounts({
+            counter: counterPda,
+            authority: newAuthorityKeypair.publicKey,
+          })
+          .signers([newAuthorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintHasOne");
+      }
+    });
+  });
+
+  describe("Close Counter", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(3), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should close the counter and refund rent to the authority", async () => {
+      const balanceBefore = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+
+      await program.methods
+        .close()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          meta: null,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const balanceAfter = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      expect(balanceAfter).to.be.greaterThan(balanceBefore);
+
+      // The account no longer exists, so reads should fail
+      try {
+        await program.methods
+          .getCount()
+          .accounts({
+            counter: counterPda,
+          })
+          .view();
+
+        expect.fail("Expected read of closed counter to fail");
+      } catch (error) {
+        expect(error.message).to.include("AccountNotInitialized");
+      }
+    });
+
+    it("Should close the counter's metadata along with it", async () => {
+      const [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .close()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          meta: metaPda,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(metaPda)).to.be.null;
+    });
+  });
+
+  describe("Events", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(() => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    it("Should emit events for initialize, increment and transfer", async () => {
+      const initTx = await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      const initEvents = await fetchEvents(initTx);
+      expect(initEvents.map((e) => e.name)).to.include("counterInitialized");
+      expect(initEvents[0].data.count.toString()).to.equal("5");
+
+      const incrementTx = await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      const incrementEvents = await fetchEvents(incrementTx);
+      expect(incrementEvents[0].name).to.equal("counterIncremented");
+      expect(incrementEvents[0].data.count.toString()).to.equal("6");
+      expect(incrementEvents[0].data.counter.toString()).to.equal(
+        counterPda.toString()
+      );
+
+      const transferTx = await program.methods
+        .transferAuthority(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      const transferEvents = await fetchEvents(transferTx);
+      expect(transferEvents[0].name).to.equal("authorityTransferred");
+      expect(transferEvents[0].data.newAuthority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Maximum Value", () => {
+    it("Should stop incrementing at the configured cap", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(5),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // Increment up to the cap
+      for (let i = 0; i < 5; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+      expect(counterAccount.maxValue.toString()).to.equal("5");
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected max value error");
+      } catch (error) {
+        expect(error.message).to.include("MaxValueReached");
+      }
+    });
+  });
+
+  describe("Pause Counter", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should block increments while paused and allow them after unpausing", async () => {
+      await program.methods
+        .pause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected paused error");
+      } catch (error) {
+        expect(error.message).to.include("CounterPaused");
+      }
+
+      await program.methods
+        .unpause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.isPaused).to.equal(false);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should resume automatically once a timed pause expires", async () => {
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+
+      await program.methods
+        .pauseUntil(new anchor.BN(now + 2))
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected paused error");
+      } catch (error) {
+        expect(error.message).to.include("CounterPaused");
+      }
+
+      // Wait past the expiry; no unpause is sent
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Two-Step Authority Transfer", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .proposeAuthority(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should hand off authority once the pending authority accepts", async () => {
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.pendingAuthority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+
+      await program.methods
+        .acceptAuthority()
+        .accounts({
+          counter: counterPda,
+          newAuthority: newAuthorityKeypair.publicKey,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+      expect(counterAccount.pendingAuthority.toString()).to.equal(
+        anchor.web3.PublicKey.default.toString()
+      );
+    });
+
+    it("Should reject acceptance by the wrong signer", async () => {
+      const wrongSigner = anchor.web3.Keypair.generate();
+
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({
+            counter: counterPda,
+            newAuthority: wrongSigner.publicKey,
+          })
+          .signers([wrongSigner])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+    });
+
+    it("Should let the current authority cancel the transfer", async () => {
+      await program.methods
+        .cancelAuthorityTransfer()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({
+            counter: counterPda,
+            newAuthority: newAuthorityKeypair.publicKey,
+          })
+          .signers([newAuthorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+    });
+
+    it("Should drop the proposal when authority is transferred directly", async () => {
+      const directOwner = anchor.web3.Keypair.generate();
+      await program.methods
+        .transferAuthority(directOwner.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .acceptAuthority()
+          .accounts({
+            counter: counterPda,
+            newAuthority: newAuthorityKeypair.publicKey,
+          })
+          .signers([newAuthorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("NotPendingAuthority");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        directOwner.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Stats", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should count increments but not decrements", async () => {
+      for (let i = 0; i < 2; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      await program.methods
+        .decrement()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const stats = await program.methods
+        .getStats()
+        .accounts({
+          counter: counterPda,
+        })
+        .view();
+
+      expect(stats.count.toString()).to.equal("1");
+      expect(stats.totalIncrements.toString()).to.equal("2");
+    });
+  });
+
+  describe("Rate Limit", () => {
+    it("Should reject a second increment inside the interval", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          minIntervalSeconds: new anchor.BN(60),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected rate limit error");
+      } catch (error) {
+        expect(error.message).to.include("RateLimited");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Batch Increment", () => {
+    it("Should increment three counters in a single call", async () => {
+      const counterPdas: anchor.web3.PublicKey[] = [];
+
+      // Each PDA is derived from its creator, so create three counters with
+      // separate authorities and hand them all to the same owner
+      for (let i = 0; i < 3; i++) {
+        const creator =
+          i === 0 ? authorityKeypair : anchor.web3.Keypair.generate();
+        if (i > 0) {
+          await provider.connection.confirmTransaction(
+            await provider.connection.requestAirdrop(
+              creator.publicKey,
+              2 * anchor.web3.LAMPORTS_PER_SOL
+            ),
+            "confirmed"
+          );
+        }
+
+        const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("counter"), creator.publicKey.toBuffer()],
+          program.programId
+        );
+
+        await program.methods
+          .initialize(new anchor.BN(i * 10), defaultParams())
+          .accounts({
+            counter: counterPda,
+            authority: creator.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([creator])
+          .rpc();
+
+        if (i > 0) {
+          await program.methods
+            .transferAuthority(authorityKeypair.publicKey)
+            .accounts({
+              counter: counterPda,
+              authority: creator.publicKey,
+            })
+            .signers([creator])
+            .rpc();
+        }
+
+        counterPdas.push(counterPda);
+      }
+
+      const remainingAccounts = counterPdas.map((pubkey) => ({
+        pubkey,
+        isWritable: true,
+        isSigner: false,
+      }));
+
+      const returnData = await simulateReturnData(
+        program.methods
+          .batchIncrement()
+          .accounts({
+            authority: authorityKeypair.publicKey,
+          })
+          .remainingAccounts(remainingAccounts)
+          .signers([authorityKeypair])
+      );
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("3");
+
+      const batchTx = await program.methods
+        .batchIncrement()
+        .accounts({
+          authority: authorityKeypair.publicKey,
+        })
+        .remainingAccounts(remainingAccounts)
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      for (let i = 0; i < 3; i++) {
+        const counterAccount = await program.account.counter.fetch(
+          counterPdas[i]
+        );
+        expect(counterAccount.count.toString()).to.equal(
+          (i * 10 + 1).toString()
+        );
+      }
+
+      // One event for the whole batch, listing every counter in order
+      const events = await fetchEvents(batchTx);
+      expect(events).to.have.length(1);
+      expect(events[0].name).to.equal("batchIncremented");
+      expect(
+        events[0].data.entries.map((entry) => [
+          entry.counter.toString(),
+          entry.count.toNumber(),
+        ])
+      ).to.deep.equal(
+        counterPdas.map((pubkey, i) => [pubkey.toString(), i * 10 + 1])
+      );
+    });
+
+    it("Should reject a batch larger than MAX_BATCH before doing any work", async () => {
+      // The size check runs first, so the accounts need not be counters
+      const remainingAccounts = Array.from({ length: 11 }, () => ({
+        pubkey: anchor.web3.Keypair.generate().publicKey,
+        isWritable: true,
+        isSigner: false,
+      }));
+
+      try {
+        await program.methods
+          .batchIncrement()
+          .accounts({
+            authority: authorityKeypair.publicKey,
+          })
+          .remainingAccounts(remainingAccounts)
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected batch too large error");
+      } catch (error) {
+        expect(error.message).to.include("BatchTooLarge");
+      }
+    });
+  });
+
+  describe("Labels", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let metaPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should start with an empty label", async () => {
+      const meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.label).to.equal("");
+    });
+
+    it("Should rename the counter", async () => {
+      await program.methods
+        .setLabel("signups")
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.label).to.equal("signups");
+    });
+
+    it("Should reject labels longer than 32 bytes", async () => {
+      try {
+        await program.methods
+          .setLabel("x".repeat(33))
+          .accounts({
+            counter: counterPda,
+            meta: metaPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected label length error");
+      } catch (error) {
+        expect(error.message).to.include("LabelTooLong");
+      }
+    });
+  });
+
+  describe("Delegate", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let delegateKeypair: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      delegateKeypair = anchor.web3.Keypair.generate();
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setDelegate(delegateKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should let the delegate increment", async () => {
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: delegateKeypair.publicKey,
+        })
+        .signers([delegateKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should not let the delegate transfer authority", async () => {
+      try {
+        await program.methods
+          .transferAuthority(delegateKeypair.publicKey)
+          .accounts({
+            counter: counterPda,
+            authority: delegateKeypair.publicKey,
+          })
+          .signers([delegateKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintHasOne");
+      }
+    });
+
+    it("Should block the delegate after it is revoked", async () => {
+      await program.methods
+        .revokeDelegate()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: delegateKeypair.publicKey,
+          })
+          .signers([delegateKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("Minimum Value", () => {
+    it("Should not decrement below the configured floor", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(11), {
+          ...defaultParams(),
+          minValue: new anchor.BN(10),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .decrement()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .decrement()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected min value error");
+      } catch (error) {
+        expect(error.message).to.include("MinValueReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+
+    it("Should reset to the floor rather than zero", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(15), {
+          ...defaultParams(),
+          minValue: new anchor.BN(10),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .reset()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+  });
+
+  describe("Checkpoints", () => {
+    it("Should restore the counter to the saved checkpoint", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .incrementBy(new anchor.BN(3))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .saveCheckpoint()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .incrementBy(new anchor.BN(4))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .restoreCheckpoint()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.checkpoint.toString()).to.equal("3");
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+
+    it("Should reject a checkpoint outside bounds tightened since the save", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(8), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const checkpointAccounts = {
+        counter: counterPda,
+        authority: authorityKeypair.publicKey,
+      };
+      await program.methods
+        .saveCheckpoint()
+        .accounts(checkpointAccounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .resetTo(new anchor.BN(2))
+        .accounts(checkpointAccounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .configure({
+          maxValue: new anchor.BN(5),
+          minIntervalSeconds: null,
+          label: null,
+        })
+        .accounts({ ...checkpointAccounts, meta: null })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .restoreCheckpoint()
+          .accounts(checkpointAccounts)
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected max value error");
+      } catch (error) {
+        expect(error.message).to.include("MaxValueReached");
+      }
+    });
+  });
+
+  describe("Threshold Authorities", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let secondOwner: anchor.web3.Keypair;
+    let thirdOwner: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      secondOwner = anchor.web3.Keypair.generate();
+      thirdOwner = anchor.web3.Keypair.generate();
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // 2-of-3 co-ownership
+      await program.methods
+        .setAuthorities(
+          [
+            authorityKeypair.publicKey,
+            secondOwner.publicKey,
+            thirdOwner.publicKey,
+          ],
+          2
+        )
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should transfer authority with two of three signers", async () => {
+      await program.methods
+        .transferAuthority(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .remainingAccounts([
+          { pubkey: secondOwner.publicKey, isWritable: false, isSigner: true },
+        ])
+        .signers([authorityKeypair, secondOwner])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+
+    it("Should reject a transfer with only one signer", async () => {
+      try {
+        await program.methods
+          .transferAuthority(newAuthorityKeypair.publicKey)
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("InsufficientSigners");
+      }
+    });
+  });
+
+  describe("Increment With Memo", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should log the memo with the increment", async () => {
+      const tx = await program.methods
+        .incrementWithMemo("restocked shelf 4")
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      const txDetails = await provider.connection.getTransaction(tx, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      expect(txDetails.meta.logMessages.join("\n")).to.include(
+        "Memo: restocked shelf 4"
+      );
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject memos longer than 128 bytes", async () => {
+      try {
+        await program.methods
+          .incrementWithMemo("m".repeat(129))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected memo length error");
+      } catch (error) {
+        expect(error.message).to.include("MemoTooLong");
+      }
+    });
+  });
+
+  describe("Counter Info", () => {
+    it("Should return the full account snapshot", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(8), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(100),
+          minValue: new anchor.BN(2),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const info = await program.methods
+        .getCounterInfo()
+        .accounts({
+          counter: counterPda,
+        })
+        .view();
+      const counterAccount = await program.account.counter.fetch(counterPda);
+
+      expect(info.count.toString()).to.equal("8");
+      expect(info.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+      expect(info.lastUpdated.toString()).to.equal(
+        counterAccount.lastUpdated.toString()
+      );
+      expect(info.maxValue.toString()).to.equal("100");
+      expect(info.minValue.toString()).to.equal("2");
+      expect(info.isPaused).to.equal(false);
+    });
+  });
+
+  describe("Keypair Counters", () => {
+    it("Should create two independent counters for the same authority", async () => {
+      const first = anchor.web3.Keypair.generate();
+      const second = anchor.web3.Keypair.generate();
+
+      for (const [counterKeypair, initialValue] of [
+        [first, 1],
+        [second, 20],
+      ] as [anchor.web3.Keypair, number][]) {
+        await program.methods
+          .initializeKeypair(new anchor.BN(initialValue), defaultParams())
+          .accounts({
+            counter: counterKeypair.publicKey,
+            authority: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([authorityKeypair, counterKeypair])
+          .rpc();
+      }
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: first.publicKey,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const firstAccount = await program.account.counter.fetch(first.publicKey);
+      const secondAccount = await program.account.counter.fetch(
+        second.publicKey
+      );
+      expect(firstAccount.count.toString()).to.equal("2");
+      expect(secondAccount.count.toString()).to.equal("20");
+      expect(firstAccount.authority.toString()).to.equal(
+        secondAccount.authority.toString()
+      );
+    });
+  });
+
+  describe("Saturating Increment", () => {
+    it("Should leave the value unchanged at u64::MAX and log saturation", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const maxU64 = new anchor.BN("18446744073709551615");
+
+      await program.methods
+        .initialize(maxU64, defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const tx = await program.methods
+        .incrementSaturating()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      const txDetails = await provider.connection.getTransaction(tx, {
+        commitment: "confirmed",
+        maxSupportedTransactionVersion: 0,
+      });
+      expect(txDetails.meta.logMessages.join("\n")).to.include(
+        "Counter saturated at"
+      );
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal(maxU64.toString());
+    });
+  });
+
+  describe("Creation Timestamp", () => {
+    it("Should keep created_at fixed while last_updated moves", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const initial = await program.account.counter.fetch(counterPda);
+      expect(initial.createdAt.toString()).to.equal(
+        initial.lastUpdated.toString()
+      );
+
+      for (let i = 0; i < 3; i++) {
+        // Let the clock advance between increments
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.createdAt.toString()).to.equal(
+        initial.createdAt.toString()
+      );
+      expect(counterAccount.lastUpdated.toNumber()).to.be.greaterThan(
+        counterAccount.createdAt.toNumber()
+      );
+    });
+  });
+
+  describe("Forced Increment", () => {
+    it("Should bypass the rate limit for the authority", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          minIntervalSeconds: new anchor.BN(60),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .incrementForce()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("2");
+    });
+  });
+
+  describe("Global Config", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    it("Should initialize the config with the caller as admin", async () => {
+      try {
+        await program.methods
+          .initializeConfig(new anchor.BN(50), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected the config to exist already");
+      } catch (error) {
+        expect(error.message).to.include("already in use");
+      }
+
+      await program.methods
+        .updateConfig(new anchor.BN(50), new anchor.BN(0))
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+      const config = await program.account.config.fetch(configPda);
+      expect(config.admin.toString()).to.equal(provider.publicKey.toString());
+      expect(config.defaultMaxValue.toString()).to.equal("50");
+    });
+
+    it("Should apply config defaults to settings left unset", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.maxValue.toString()).to.equal("50");
+      expect(counterAccount.minIntervalSeconds.toString()).to.equal("0");
+    });
+
+    it("Should only let the admin update the config", async () => {
+      try {
+        await program.methods
+          .updateConfig(new anchor.BN(1), new anchor.BN(1))
+          .accounts({
+            config: configPda,
+            admin: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintHasOne");
+      }
+
+      await program.methods
+        .updateConfig(new anchor.BN(0), new anchor.BN(0))
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+      const config = await program.account.config.fetch(configPda);
+      expect(config.defaultMaxValue.toString()).to.equal("0");
+    });
+  });
+
+  describe("Signed Increment", () => {
+    it("Should accept a relayed signed increment and reject a replay", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // The authority signs (counter address || nonce) off chain
+      const message = Buffer.concat([
+        counterPda.toBuffer(),
+        new anchor.BN(0).toArrayLike(Buffer, "le", 8),
+      ]);
+      const ed25519Ix =
+        anchor.web3.Ed25519Program.createInstructionWithPrivateKey({
+          privateKey: authorityKeypair.secretKey,
+          message,
+        });
+      // web3.js lays out the instruction as header, public key, signature
+      const signature = Array.from(ed25519Ix.data.subarray(48, 112));
+
+      // The provider wallet relays the transaction and pays the fees
+      await program.methods
+        .incrementSigned(signature, message)
+        .accounts({
+          counter: counterPda,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([ed25519Ix])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+      expect(counterAccount.nonce.toString()).to.equal("1");
+
+      try {
+        // The compute budget instruction keeps this from being deduplicated
+        // as the exact same transaction
+        await program.methods
+          .incrementSigned(signature, message)
+          .accounts({
+            counter: counterPda,
+            instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+          })
+          .preInstructions([
+            anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+              units: 200_000,
+            }),
+            ed25519Ix,
+          ])
+          .rpc();
+
+        expect.fail("Expected replay to fail");
+      } catch (error) {
+        expect(error.message).to.include("InvalidSignedMessage");
+      }
+    });
+  });
+
+  describe("Drain", () => {
+    it("Should return the drained value and zero the counter", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(42), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const drainMethod = program.methods
+        .drain()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair]);
+
+      const returnData = await simulateReturnData(drainMethod);
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("42");
+
+      await drainMethod.rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+
+    it("Should stop at the floor and return only what was above it", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(42), {
+          ...defaultParams(),
+          minValue: new anchor.BN(10),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const drainMethod = program.methods
+        .drain()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair]);
+
+      const returnData = await simulateReturnData(drainMethod);
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("32");
+
+      await drainMethod.rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+  });
+
+  describe("Migration", () => {
+    // Preloaded by Anchor.toml with the original 56-byte account layout
+    const legacyCounter = new anchor.web3.PublicKey(
+      "DwmqH53LAoNMqJtq2xMwZJkxt1ZEizzkFgMkyosQnfjz"
+    );
+    const legacyAuthority = anchor.web3.Keypair.fromSecretKey(
+      Uint8Array.from(
+        JSON.parse(
+          fs.readFileSync("tests/fixtures/legacy-authority.json", "utf8")
+        )
+      )
+    );
+
+    it("Should grow a legacy counter and be idempotent", async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          legacyAuthority.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+
+      const before = await provider.connection.getAccountInfo(legacyCounter);
+      expect(before.data.length).to.equal(56);
+
+      const migrate = () =>
+        program.methods
+          .migrate()
+          .accounts({
+            counter: legacyCounter,
+            authority: legacyAuthority.publicKey,
+          })
+          .signers([legacyAuthority])
+          .rpc();
+
+      await migrate();
+
+      const after = await provider.connection.getAccountInfo(legacyCounter);
+      expect(after.data.length).to.be.greaterThan(56);
+
+      const counterAccount = await program.account.counter.fetch(
+        legacyCounter
+      );
+      expect(counterAccount.count.toString()).to.equal("7");
+      expect(counterAccount.authority.toString()).to.equal(
+        legacyAuthority.publicKey.toString()
+      );
+      expect(counterAccount.isPaused).to.equal(false);
+      expect(counterAccount.version).to.equal(2);
+      const [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), legacyCounter.toBuffer()],
+        program.programId
+      );
+      const meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.counter.toString()).to.equal(legacyCounter.toString());
+      expect(meta.label).to.equal("");
+
+      // Running it again leaves the account untouched
+      await migrate();
+
+      const again = await provider.connection.getAccountInfo(legacyCounter);
+      expect(again.data.length).to.equal(after.data.length);
+      expect(again.lamports).to.equal(after.lamports);
+    });
+
+    it("Should not create metadata for an up-to-date counter", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      const balanceBefore = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+
+      await program.methods
+        .migrate()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(metaPda)).to.be.null;
+      // Only the transaction fee is paid
+      const balanceAfter = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      expect(balanceBefore - balanceAfter).to.be.lessThan(10000);
+    });
+  });
+
+  describe("Versioning", () => {
+    it("Should reject writes guarded by a mismatched version", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.version).to.equal(1);
+
+      const guardedIncrement = async (expectedVersion: number) =>
+        program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .preInstructions([
+            await program.methods
+              .checkVersion(expectedVersion)
+              .accounts({ counter: counterPda })
+              .instruction(),
+          ])
+          .signers([authorityKeypair])
+          .rpc();
+
+      try {
+        await guardedIncrement(0);
+        expect.fail("Expected version mismatch error");
+      } catch (error) {
+        expect(error.message).to.include("VersionMismatch");
+      }
+
+      await guardedIncrement(1);
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Conditional Increment", () => {
+    it("Should only increment when the expected value matches", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .incrementIf(new anchor.BN(4))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected unexpected value error");
+      } catch (error) {
+        expect(error.message).to.include("UnexpectedValue");
+      }
+
+      await program.methods
+        .incrementIf(new anchor.BN(5))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("6");
+    });
+  });
+
+  describe("Last Modifier", () => {
+    it("Should record the delegate that last incremented", async () => {
+      const delegateKeypair = anchor.web3.Keypair.generate();
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      let lastModifier = await program.methods
+        .getLastModifier()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(lastModifier.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+
+      await program.methods
+        .setDelegate(delegateKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: delegateKeypair.publicKey,
+        })
+        .signers([delegateKeypair])
+        .rpc();
+
+      lastModifier = await program.methods
+        .getLastModifier()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(lastModifier.toString()).to.equal(
+        delegateKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Set Count", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(10), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(100),
+          minValue: new anchor.BN(5),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const setCount = (value: number) =>
+      program.methods
+        .setCount(new anchor.BN(value))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should set a value within bounds", async () => {
+      await setCount(77);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("77");
+    });
+
+    it("Should reject values outside the bounds", async () => {
+      try {
+        await setCount(101);
+        expect.fail("Expected max value error");
+      } catch (error) {
+        expect(error.message).to.include("MaxValueReached");
+      }
+
+      try {
+        await setCount(4);
+        expect.fail("Expected min value error");
+      } catch (error) {
+        expect(error.message).to.include("MinValueReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+
+    it("Should log the offending value and bound", async () => {
+      try {
+        await setCount(101);
+        expect.fail("Expected max value error");
+      } catch (error) {
+        expect(error.logs.join("\n")).to.include(
+          "Value 101 exceeds max_value 100"
+        );
+      }
+
+      try {
+        await setCount(4);
+        expect.fail("Expected min value error");
+      } catch (error) {
+        expect(error.logs.join("\n")).to.include(
+          "Value 4 is below min_value 5"
+        );
+      }
+    });
+  });
+
+  describe("Apply Deltas", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    const initialize = (initialValue: anchor.BN) =>
+      program.methods
+        .initialize(initialValue, defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    const applyDeltas = (deltas: number[]) =>
+      program.methods
+        .applyDeltas(deltas.map((delta) => new anchor.BN(delta)))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should fold a mixed sequence of deltas", async () => {
+      await initialize(new anchor.BN(10));
+      await applyDeltas([5, -3, 7, -10, 1]);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+
+    it("Should abort the whole list on overflow", async () => {
+      await initialize(new anchor.BN("18446744073709551610"));
+
+      try {
+        await applyDeltas([-5, 3, 10]);
+        expect.fail("Expected overflow error");
+      } catch (error) {
+        expect(error.message).to.include("Overflow");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("18446744073709551610");
+    });
+  });
+
+  describe("Global Pause", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    const setGlobalPause = (paused: boolean) =>
+      program.methods
+        .setGlobalPause(paused)
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+    it("Should block increments across counters", async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      const owners = [authorityKeypair, newAuthorityKeypair];
+      const counters = owners.map(
+        (owner) =>
+          anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("counter"), owner.publicKey.toBuffer()],
+            program.programId
+          )[0]
+      );
+
+      const increment = (index: number) =>
+        program.methods
+          .increment()
+          .accounts({
+            counter: counters[index],
+            authority: owners[index].publicKey,
+            config: configPda,
+          })
+          .signers([owners[index]])
+          .rpc();
+
+      for (const [index, owner] of owners.entries()) {
+        await program.methods
+          .initialize(new anchor.BN(0), defaultParams())
+          .accounts({
+            counter: counters[index],
+            authority: owner.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([owner])
+          .rpc();
+      }
+
+      await setGlobalPause(true);
+      try {
+        for (const index of [0, 1]) {
+          try {
+            await increment(index);
+            expect.fail("Expected global pause error");
+          } catch (error) {
+            expect(error.message).to.include("GloballyPaused");
+          }
+        }
+      } finally {
+        await setGlobalPause(false);
+      }
+
+      await increment(0);
+      await increment(1);
+
+      for (const counter of counters) {
+        const counterAccount = await program.account.counter.fetch(counter);
+        expect(counterAccount.count.toString()).to.equal("1");
+      }
+    });
+
+    it("Should block settings changes such as authority transfers", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await setGlobalPause(true);
+      try {
+        await program.methods
+          .transferAuthority(newAuthorityKeypair.publicKey)
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected global pause error");
+      } catch (error) {
+        expect(error.message).to.include("GloballyPaused");
+      } finally {
+        await setGlobalPause(false);
+      }
+    });
+
+    it("Should reject a call that leaves the config out", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // An omitted optional account is sent as the program ID
+      const ix = await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .instruction();
+      const configKey = ix.keys.find((key) => key.pubkey.equals(configPda));
+      configKey.pubkey = program.programId;
+      configKey.isWritable = false;
+
+      await setGlobalPause(true);
+      try {
+        await provider.sendAndConfirm(new anchor.web3.Transaction().add(ix), [
+          authorityKeypair,
+        ]);
+        expect.fail("Expected config account error");
+      } catch (error) {
+        expect(error.logs.join("\n")).to.include("AccountOwnedByWrongProgram");
+      } finally {
+        await setGlobalPause(false);
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+  });
+
+  describe("History", () => {
+    it("Should keep the eight most recent values in order", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (let i = 0; i < 10; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const history = await program.methods
+        .getHistory()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(history.map((value) => value.toNumber())).to.deep.equal([
+        3, 4, 5, 6, 7, 8, 9, 10,
+      ]);
+    });
+
+    it("Should return single history points by index", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (let i = 0; i < 3; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const newest = await program.methods
+        .getCountAt(7)
+        .accounts({ counter: counterPda })
+        .view();
+      expect(newest.toNumber()).to.equal(3);
+
+      try {
+        await program.methods
+          .getCountAt(8)
+          .accounts({ counter: counterPda })
+          .view();
+        expect.fail("Expected index out of range error");
+      } catch (error) {
+        expect(error.message).to.include("IndexOutOfRange");
+      }
+    });
+  });
+
+  describe("Wrapping Counters", () => {
+    it("Should wrap back to zero after exceeding the max", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(3),
+          wrap: true,
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const values = [];
+      let signature: string;
+      for (let i = 0; i < 4; i++) {
+        signature = await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc({ commitment: "confirmed" });
+
+        const counterAccount = await program.account.counter.fetch(counterPda);
+        values.push(counterAccount.count.toNumber());
+      }
+      expect(values).to.deep.equal([1, 2, 3, 0]);
+
+      const events = await fetchEvents(signature);
+      expect(events.map((event) => event.name)).to.include("counterWrapped");
+    });
+  });
+
+  describe("Return Data", () => {
+    it("Should return the new count from increment and decrement", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(10), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const accounts = {
+        counter: counterPda,
+        authority: authorityKeypair.publicKey,
+      };
+      const incrementMethod = program.methods
+        .increment()
+        .accounts(accounts)
+        .signers([authorityKeypair]);
+
+      let returnData = await simulateReturnData(incrementMethod);
+      await incrementMethod.rpc();
+
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("11");
+      expect(counterAccount.count.toString()).to.equal("11");
+
+      const decrementMethod = program.methods
+        .decrement()
+        .accounts(accounts)
+        .signers([authorityKeypair]);
+
+      returnData = await simulateReturnData(decrementMethod);
+      await decrementMethod.rpc();
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("10");
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+  });
+
+  describe("Initialize If Needed", () => {
+    it("Should create the counter once and leave it untouched after", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const initializeOrGet = (initialValue: number) =>
+        program.methods
+          .initializeOrGet(new anchor.BN(initialValue))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([authorityKeypair]);
+
+      await initializeOrGet(5).rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const returnData = await simulateReturnData(initializeOrGet(99));
+      expect(new anchor.BN(returnData, "le").toString()).to.equal("6");
+
+      await initializeOrGet(99).rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("6");
+    });
+  });
+
+  describe("Registry", () => {
+    it("Should track created counters and drop closed ones", async () => {
+      const [registryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("registry"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([counterKeypair, authorityKeypair])
+        .rpc();
+
+      let registry = await program.account.registry.fetch(registryPda);
+      expect(registry.counters.map((key) => key.toString())).to.deep.equal([
+        counterPda.toString(),
+        counterKeypair.publicKey.toString(),
+      ]);
+
+      await program.methods
+        .close()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          meta: null,
+          registry: registryPda,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      registry = await program.account.registry.fetch(registryPda);
+      expect(registry.counters.map((key) => key.toString())).to.deep.equal([
+        counterKeypair.publicKey.toString(),
+      ]);
+    });
+
+    it("Should let a new owner without a registry close a transferred counter", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .transferAuthority(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .close()
+        .accounts({
+          counter: counterPda,
+          authority: newAuthorityKeypair.publicKey,
+          meta: null,
+          registry: null,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetchNullable(counterPda);
+      expect(counterAccount).to.equal(null);
+    });
+  });
+
+  describe("Decrement With Burn", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let mint: anchor.web3.PublicKey;
+    let tokenAccount: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      mint = await createMint(authorityKeypair.publicKey);
+      tokenAccount = await createTokenAccount(
+        mint,
+        authorityKeypair.publicKey
+      );
+      await mintTo(mint, tokenAccount, authorityKeypair, 10);
+    });
+
+    const decrementWithBurn = (amount: number) =>
+      program.methods
+        .decrementWithBurn(new anchor.BN(amount))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          tokenAccount,
+          mint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should decrement after burning tokens", async () => {
+      await decrementWithBurn(4);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("4");
+
+      const balance = await provider.connection.getTokenAccountBalance(
+        tokenAccount
+      );
+      expect(balance.value.amount).to.equal("6");
+    });
+
+    it("Should fail when the token balance is insufficient", async () => {
+      try {
+        await decrementWithBurn(11);
+        expect.fail("Expected insufficient funds error");
+      } catch (error) {
+        expect(error.message).to.include("0x1");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+    });
+  });
+
+  describe("Time-Weighted Accumulator", () => {
+    it("Should accrue the held value times elapsed seconds", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(10), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const before = await program.account.counter.fetch(counterPda);
+      expect(before.accumulator.toString()).to.equal("0");
+
+      // Hold the value for a couple of seconds before changing it
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const after = await program.account.counter.fetch(counterPda);
+      const elapsed = after.lastUpdated.sub(before.lastUpdated);
+      expect(elapsed.toNumber()).to.be.greaterThan(0);
+      expect(after.accumulator.toString()).to.equal(
+        elapsed.muln(10).toString()
+      );
+
+      const accrued = await program.methods
+        .getAccumulator()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(accrued.gte(after.accumulator)).to.equal(true);
+    });
+  });
+
+  describe("Batch Initialize", () => {
+    it("Should create four indexed counters in one call", async () => {
+      const counters = [0, 1, 2, 3].map(
+        (index) =>
+          anchor.web3.PublicKey.findProgramAddressSync(
+            [
+              Buffer.from("counter"),
+              authorityKeypair.publicKey.toBuffer(),
+              Buffer.from([index]),
+            ],
+            program.programId
+          )[0]
+      );
+
+      await program.methods
+        .batchInitialize(counters.length, new anchor.BN(7))
+        .accounts({
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: true,
+          }))
+        )
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (const counter of counters) {
+        const counterAccount = await program.account.counter.fetch(counter);
+        expect(counterAccount.count.toString()).to.equal("7");
+        expect(counterAccount.authority.toString()).to.equal(
+          authorityKeypair.publicKey.toString()
+        );
+      }
+    });
+
+    it("Should reject an account at the wrong address", async () => {
+      const [wrongCounter] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("counter"),
+          authorityKeypair.publicKey.toBuffer(),
+          Buffer.from([1]),
+        ],
+        program.programId
+      );
+
+      try {
+        await program.methods
+          .batchInitialize(1, new anchor.BN(0))
+          .accounts({
+            authority: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .remainingAccounts([
+            { pubkey: wrongCounter, isSigner: false, isWritable: true },
+          ])
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected invalid address error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidCounterAddress");
+      }
+    });
+  });
+
+  describe("Freeze Authority", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let freezeKeypair: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      freezeKeypair = anchor.web3.Keypair.generate();
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          freezeAuthority: freezeKeypair.publicKey,
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should let the freeze authority pause but not increment", async () => {
+      await program.methods
+        .pause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: freezeKeypair.publicKey,
+        })
+        .signers([freezeKeypair])
+        .rpc();
+
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.isPaused).to.equal(true);
+
+      await program.methods
+        .unpause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: freezeKeypair.publicKey,
+        })
+        .signers([freezeKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: freezeKeypair.publicKey,
+          })
+          .signers([freezeKeypair])
+          .rpc();
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+
+    it("Should let the authority increment but not pause", async () => {
+      try {
+        await program.methods
+          .pause()
+          .accounts({
+            counter: counterPda,
+            freezeAuthority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("ConstraintHasOne");
+      }
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+      expect(counterAccount.isPaused).to.equal(false);
+    });
+  });
+
+  describe("Geometric Increment", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    const initialize = (initialValue: anchor.BN) =>
+      program.methods
+        .initialize(initialValue, defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    const incrementGeometric = () =>
+      program.methods
+        .incrementGeometric()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should double the value starting from zero", async () => {
+      await initialize(new anchor.BN(0));
+
+      const values = [];
+      for (let i = 0; i < 4; i++) {
+        await incrementGeometric();
+        const counterAccount = await program.account.counter.fetch(counterPda);
+        values.push(counterAccount.count.toNumber());
+      }
+      expect(values).to.deep.equal([1, 2, 4, 8]);
+    });
+
+    it("Should fail on overflow near u64::MAX", async () => {
+      await initialize(new anchor.BN("9223372036854775808"));
+
+      try {
+        await incrementGeometric();
+        expect.fail("Expected overflow error");
+      } catch (error) {
+        expect(error.message).to.include("Overflow");
+      }
+    });
+  });
+
+  describe("Bounty", () => {
+    it("Should pay the incrementer from the vault until it runs dry", async () => {
+      const bounty = 1_000_000;
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("bounty"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setBounty(new anchor.BN(bounty))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // Rent-exempt reserve plus enough for exactly two payouts
+      const reserve =
+        await provider.connection.getMinimumBalanceForRentExemption(0);
+      await program.methods
+        .fundBounty(new anchor.BN(reserve + 2 * bounty))
+        .accounts({
+          counter: counterPda,
+          funder: provider.publicKey,
+        })
+        .rpc();
+
+      const increment = () =>
+        program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+      const balanceBefore = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      await increment();
+      await increment();
+
+      const balanceAfter = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      expect(balanceAfter - balanceBefore).to.equal(2 * bounty);
+      expect(await provider.connection.getBalance(vaultPda)).to.equal(
+        reserve
+      );
+
+      try {
+        await increment();
+        expect.fail("Expected bounty exhausted error");
+      } catch (error) {
+        expect(error.message).to.include("BountyExhausted");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("2");
+    });
+  });
+
+  describe("CPI", () => {
+    const callerProgram = anchor.workspace
+      .CounterCaller as Program<CounterCaller>;
+    const [callerAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("caller-authority")],
+      callerProgram.programId
+    );
+
+    it("Should increment through another program's PDA signer", async () => {
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([counterKeypair, authorityKeypair])
+        .rpc();
+
+      // Hand the counter to the caller program's PDA
+      await program.methods
+        .transferAuthority(callerAuthority)
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await callerProgram.methods
+        .incrementViaCpi()
+        .accounts({
+          counter: counterKeypair.publicKey,
+          counterProgram: program.programId,
+        })
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(
+        counterKeypair.publicKey
+      );
+      expect(counterAccount.count.toString()).to.equal("1");
+      expect(counterAccount.lastModifier.toString()).to.equal(
+        callerAuthority.toString()
+      );
+    });
+
+    it("Should reject a CPI from a program that is not the authority", async () => {
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([counterKeypair, authorityKeypair])
+        .rpc();
+
+      try {
+        await callerProgram.methods
+          .incrementViaCpi()
+          .accounts({
+            counter: counterKeypair.publicKey,
+            counterProgram: program.programId,
+          })
+          .rpc();
+        expect.fail("Expected transaction to fail");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+
+    it("Should increment through the PDA authority path", async () => {
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([counterKeypair, authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .transferAuthority(callerAuthority)
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await callerProgram.methods
+        .incrementAsPdaAuthority()
+        .accounts({
+          counter: counterKeypair.publicKey,
+          counterProgram: program.programId,
+        })
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(
+        counterKeypair.publicKey
+      );
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject a wallet authority on the PDA authority path", async () => {
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([counterKeypair, authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .incrementPdaAuthority(callerProgram.programId, [
+            Buffer.from("caller-authority"),
+          ])
+          .accounts({
+            counter: counterKeypair.publicKey,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected invalid PDA authority error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidPdaAuthority");
+      }
+    });
+  });
+
+  describe("State Hash", () => {
+    it("Should fold each increment into the rolling hash", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      let expected = Buffer.alloc(32);
+      expect(Buffer.from(counterAccount.stateHash)).to.deep.equal(expected);
+
+      for (let i = 0; i < 3; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        counterAccount = await program.account.counter.fetch(counterPda);
+        expected = createHash("sha256")
+          .update(expected)
+          .update(counterAccount.count.toArrayLike(Buffer, "le", 8))
+          .update(
+            counterAccount.lastUpdated.toTwos(64).toArrayLike(Buffer, "le", 8)
+          )
+          .digest();
+        expect(Buffer.from(counterAccount.stateHash)).to.deep.equal(expected);
+      }
+    });
+  });
+
+  describe("Archive", () => {
+    it("Should reject increments but stay readable while archived", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const accounts = {
+        counter: counterPda,
+        authority: authorityKeypair.publicKey,
+      };
+
+      await program.methods
+        .initialize(new anchor.BN(9), defaultParams())
+        .accounts({
+          ...accounts,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .archive()
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts(accounts)
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected archived error");
+      } catch (error) {
+        expect(error.message).to.include("CounterArchived");
+      }
+
+      const count = await program.methods
+        .getCount()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(count.toString()).to.equal("9");
+
+      await program.methods
+        .unarchive()
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+    });
+  });
+
+  describe("Priority Tips", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    let counterPda: anchor.web3.PublicKey;
+    let treasury: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      treasury = anchor.web3.Keypair.generate().publicKey;
+      await program.methods
+        .setTreasury(treasury)
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementPriority = (tip: anchor.BN) =>
+      program.methods
+        .incrementPriority(tip)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+          treasury,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should pay the tip to the treasury and increment", async () => {
+      const tip = anchor.web3.LAMPORTS_PER_SOL / 100;
+      await incrementPriority(new anchor.BN(tip));
+
+      expect(await provider.connection.getBalance(treasury)).to.equal(tip);
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject a tip larger than the signer's balance", async () => {
+      try {
+        await incrementPriority(
+          new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL)
+        );
+        expect.fail("Expected insufficient funds error");
+      } catch (error) {
+        expect(error.message).to.include("InsufficientFunds");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+  });
+
+  describe("Decay", () => {
+    it("Should lose decay_per_second for each elapsed second", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(100), {
+          ...defaultParams(),
+          decayPerSecond: new anchor.BN(5),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const before = await program.account.counter.fetch(counterPda);
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const effective = await program.methods
+        .getEffectiveCount()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(effective.toNumber()).to.be.lessThan(100);
+
+      // The view must not write the decayed value back
+      const unchanged = await program.account.counter.fetch(counterPda);
+      expect(unchanged.count.toNumber()).to.equal(100);
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const after = await program.account.counter.fetch(counterPda);
+      const elapsed = after.lastUpdated.sub(before.lastUpdated).toNumber();
+      expect(elapsed).to.be.greaterThan(0);
+      expect(after.count.toNumber()).to.equal(100 - 5 * elapsed + 1);
+    });
+
+    it("Should drain the decayed value", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(100), {
+          ...defaultParams(),
+          decayPerSecond: new anchor.BN(5),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await new Promise((resolve) => setTimeout(resolve, 2000));
+
+      const returnData = await simulateReturnData(
+        program.methods
+          .drain()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+      );
+      expect(new anchor.BN(returnData, "le").toNumber()).to.be.lessThan(100);
+    });
+  });
+
+  describe("Sum Counters", () => {
+    // Create a counter for a freshly funded authority and return its PDA
+    const createCounter = async (initialValue: number) => {
+      const creator = anchor.web3.Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          creator.publicKey,
+          2 * anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), creator.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(initialValue), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: creator.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([creator])
+        .rpc();
+
+      return counterPda;
+    };
+
+    it("Should return the total of three counters", async () => {
+      const counters = [
+        await createCounter(5),
+        await createCounter(10),
+        await createCounter(27),
+      ];
+
+      const total = await program.methods
+        .sumCounters()
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+          }))
+        )
+        .view();
+      expect(total.toString()).to.equal("42");
+    });
+
+    it("Should read the counts of four counters in order", async () => {
+      const counters = [
+        await createCounter(3),
+        await createCounter(1),
+        await createCounter(4),
+        await createCounter(1),
+      ];
+
+      const counts = await program.methods
+        .readMany()
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+          }))
+        )
+        .view();
+      expect(counts.map((count) => count.toNumber())).to.deep.equal([
+        3, 1, 4, 1,
+      ]);
+    });
+
+    it("Should return only the counters owned by the target authority", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeKeypair(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+
+      const counters = [
+        await createCounter(1),
+        counterPda,
+        await createCounter(2),
+        counterKeypair.publicKey,
+      ];
+
+      const matches = await program.methods
+        .filterByAuthority(authorityKeypair.publicKey)
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+          }))
+        )
+        .view();
+      expect(matches.map((key) => key.toString())).to.deep.equal([
+        counterPda.toString(),
+        counterKeypair.publicKey.toString(),
+      ]);
+    });
+
+    it("Should reject an account not owned by the program", async () => {
+      const counter = await createCounter(5);
+
+      try {
+        await program.methods
+          .sumCounters()
+          .remainingAccounts([
+            { pubkey: counter, isWritable: false, isSigner: false },
+            {
+              pubkey: authorityKeypair.publicKey,
+              isWritable: false,
+              isSigner: false,
+            },
+          ])
+          .view();
+
+        expect.fail("Expected view to fail");
+      } catch (error) {
+        expect(error.message).to.include("AccountOwnedByWrongProgram");
+      }
+    });
+  });
+
+  describe("Token-Gated Initialize", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    let counterPda: anchor.web3.PublicKey;
+    let mint: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      mint = await createMint(authorityKeypair.publicKey);
+      await program.methods
+        .setGateMint(mint)
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    const initializeGated = (tokenAccount: anchor.web3.PublicKey) =>
+      program.methods
+        .initializeGated(new anchor.BN(3))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+          tokenAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should create a counter for a token holder", async () => {
+      const tokenAccount = await createTokenAccount(
+        mint,
+        authorityKeypair.publicKey
+      );
+      await mintTo(mint, tokenAccount, authorityKeypair, 1);
+
+      await initializeGated(tokenAccount);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+
+    it("Should reject an authority with a zero balance", async () => {
+      const tokenAccount = await createTokenAccount(
+        mint,
+        authorityKeypair.publicKey
+      );
+
+      try {
+        await initializeGated(tokenAccount);
+        expect.fail("Expected not token holder error");
+      } catch (error) {
+        expect(error.message).to.include("NotTokenHolder");
+      }
+
+      const counterAccount = await program.account.counter.fetchNullable(
+        counterPda
+      );
+      expect(counterAccount).to.equal(null);
+    });
+  });
+
+  describe("Notes", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let metaPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const setNote = (note: string) =>
+      program.methods
+        .setNote(note)
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should set and then update the note", async () => {
+      let meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.note).to.equal("");
+
+      await setNote("Tracks sign-ups from the landing page");
+      meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.note).to.equal("Tracks sign-ups from the landing page");
+
+      const longest = "n".repeat(200);
+      await setNote(longest);
+      meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.note).to.equal(longest);
+    });
+
+    it("Should reject notes longer than 200 bytes", async () => {
+      try {
+        await setNote("x".repeat(201));
+        expect.fail("Expected note length error");
+      } catch (error) {
+        expect(error.message).to.include("NoteTooLong");
+      }
+    });
+  });
+
+  describe("Swap", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(3), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(9), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+    });
+
+    it("Should exchange the values of two counters", async () => {
+      await program.methods
+        .swap()
+        .accounts({
+          counterA: counterPda,
+          counterB: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterA = await program.account.counter.fetch(counterPda);
+      const counterB = await program.account.counter.fetch(
+        counterKeypair.publicKey
+      );
+      expect(counterA.count.toString()).to.equal("9");
+      expect(counterB.count.toString()).to.equal("3");
+      expect(counterA.lastUpdated.toString()).to.equal(
+        counterB.lastUpdated.toString()
+      );
+    });
+
+    it("Should reject swapping a counter with itself", async () => {
+      try {
+        await program.methods
+          .swap()
+          .accounts({
+            counterA: counterPda,
+            counterB: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected same account error");
+      } catch (error) {
+        expect(error.message).to.include("SameAccount");
+      }
+    });
+  });
+
+  describe("Signed Counter", () => {
+    let signedCounterPda: anchor.web3.PublicKey;
+
+    beforeEach(() => {
+      [signedCounterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("signed-counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+    });
+
+    const initializeSigned = (initialValue: anchor.BN) =>
+      program.methods
+        .initializeSignedCounter(initialValue)
+        .accounts({
+          counter: signedCounterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    const updateSigned = (direction: "increment" | "decrement") =>
+      (direction === "increment"
+        ? program.methods.incrementSignedCounter()
+        : program.methods.decrementSignedCounter()
+      )
+        .accounts({
+          counter: signedCounterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should cross zero into negative values and back", async () => {
+      await initializeSigned(new anchor.BN(1));
+
+      for (let i = 0; i < 3; i++) {
+        await updateSigned("decrement");
+      }
+      let counterAccount = await program.account.signedCounter.fetch(
+        signedCounterPda
+      );
+      expect(counterAccount.value.toString()).to.equal("-2");
+
+      await updateSigned("increment");
+      counterAccount = await program.account.signedCounter.fetch(
+        signedCounterPda
+      );
+      expect(counterAccount.value.toString()).to.equal("-1");
+    });
+
+    it("Should reject incrementing past i64::MAX", async () => {
+      await initializeSigned(new anchor.BN("9223372036854775807"));
+
+      try {
+        await updateSigned("increment");
+        expect.fail("Expected overflow error");
+      } catch (error) {
+        expect(error.message).to.include("Overflow");
+      }
+    });
+
+    it("Should reject decrementing past i64::MIN", async () => {
+      await initializeSigned(new anchor.BN("-9223372036854775808"));
+
+      try {
+        await updateSigned("decrement");
+        expect.fail("Expected underflow error");
+      } catch (error) {
+        expect(error.message).to.include("Underflow");
+      }
+    });
+  });
+
+  describe("Fee Split", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should split the payment 70/30 and increment", async () => {
+      const first = anchor.web3.Keypair.generate().publicKey;
+      const second = anchor.web3.Keypair.generate().publicKey;
+
+      await program.methods
+        .setFeeRecipients([
+          { recipient: first, bps: 7000 },
+          { recipient: second, bps: 3000 },
+        ])
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+
+      await program.methods
+        .incrementPaid(new anchor.BN(10_000_000))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+        })
+        .remainingAccounts(
+          [first, second].map((pubkey) => ({
+            pubkey,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect(await provider.connection.getBalance(first)).to.equal(7_000_000);
+      expect(await provider.connection.getBalance(second)).to.equal(3_000_000);
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject shares that do not sum to 10000", async () => {
+      try {
+        await program.methods
+          .setFeeRecipients([
+            { recipient: anchor.web3.Keypair.generate().publicKey, bps: 7000 },
+            { recipient: anchor.web3.Keypair.generate().publicKey, bps: 2000 },
+          ])
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+
+        expect.fail("Expected invalid split error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidSplit");
+      }
+    });
+  });
+
+  describe("Merkle Leaf", () => {
+    it("Should emit the same leaf until the counter changes", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(4), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // Simulated so identical calls are not rejected as duplicate transactions
+      const emitLeaf = async () => {
+        const { events } = await program.methods
+          .emitLeaf()
+          .accounts({ counter: counterPda })
+          .simulate();
+        const event = events.find((e) => e.name === "counterLeaf");
+        return Buffer.from(event.data.leaf as number[]).toString("hex");
+      };
+
+      const first = await emitLeaf();
+      const second = await emitLeaf();
+      expect(first).to.equal(second);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      const expected = createHash("sha256")
+        .update(counterPda.toBuffer())
+        .update(counterAccount.count.toArrayLike(Buffer, "le", 8))
+        .update(counterAccount.lastUpdated.toArrayLike(Buffer, "le", 8))
+        .digest("hex");
+      expect(first).to.equal(expected);
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect(await emitLeaf()).to.not.equal(first);
+    });
+  });
+
+  describe("Allowlist", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    beforeEach(async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      await program.methods
+        .addToAllowlist(authorityKeypair.publicKey)
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+    });
+
+    // Leave the allowlist empty so other tests can create counters freely
+    afterEach(async () => {
+      await program.methods
+        .removeFromAllowlist(authorityKeypair.publicKey)
+        .accounts({
+          config: configPda,
+          admin: provider.publicKey,
+        })
+        .rpc();
+    });
+
+    const initializeWithConfig = (authority: anchor.web3.Keypair) =>
+      program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("counter"), authority.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          authority: authority.publicKey,
+          config: configPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+    it("Should let an allowlisted authority create a counter", async () => {
+      await initializeWithConfig(authorityKeypair);
+
+      const config = await program.account.config.fetch(configPda);
+      expect(config.allowlist.map((key) => key.toString())).to.include(
+        authorityKeypair.publicKey.toString()
+      );
+    });
+
+    it("Should reject an authority missing from the allowlist", async () => {
+      const outsider = anchor.web3.Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          outsider.publicKey,
+          2 * anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+
+      try {
+        await initializeWithConfig(outsider);
+        expect.fail("Expected allowlist error");
+      } catch (error) {
+        expect(error.message).to.include("NotAllowlisted");
+      }
+    });
+
+    it("Should check the allowlist on keypair counters too", async () => {
+      const outsider = anchor.web3.Keypair.generate();
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          outsider.publicKey,
+          2 * anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+
+      try {
+        // The config is resolved from its fixed seeds and cannot be left out
+        await program.methods
+          .initializeKeypair(new anchor.BN(0), defaultParams())
+          .accounts({
+            counter: counterKeypair.publicKey,
+            authority: outsider.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([counterKeypair, outsider])
+          .rpc();
+        expect.fail("Expected allowlist error");
+      } catch (error) {
+        expect(error.message).to.include("NotAllowlisted");
+      }
+    });
+  });
+
+  describe("Cosigner", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let cosignerKeypair: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      cosignerKeypair = anchor.web3.Keypair.generate();
+
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          cosigner: cosignerKeypair.publicKey,
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should increment when both authority and cosigner sign", async () => {
+      await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          cosigner: cosignerKeypair.publicKey,
+        })
+        .signers([authorityKeypair, cosignerKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject an increment signed by the authority alone", async () => {
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected cosigner error");
+      } catch (error) {
+        expect(error.message).to.include("CosignerRequired");
+      }
+    });
+
+    it("Should stop requiring the old cosigner after rotation", async () => {
+      const rotated = anchor.web3.Keypair.generate();
+      await program.methods
+        .setCosigner(rotated.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+            cosigner: cosignerKeypair.publicKey,
+          })
+          .signers([authorityKeypair, cosignerKeypair])
+          .rpc();
+
+        expect.fail("Expected cosigner error");
+      } catch (error) {
+        expect(error.message).to.include("CosignerRequired");
+      }
+    });
+  });
+
+  describe("Max Change Guard", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      // Allow set_count to move at most 10% of the current value
+      await program.methods
+        .initialize(new anchor.BN(100), {
+          ...defaultParams(),
+          maxChangeBps: 1000,
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const setCount = (value: number) =>
+      program.methods
+        .setCount(new anchor.BN(value))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should allow a change within the limit", async () => {
+      await setCount(110);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("110");
+    });
+
+    it("Should reject a change over the limit", async () => {
+      try {
+        await setCount(89);
+        expect.fail("Expected change too large error");
+      } catch (error) {
+        expect(error.message).to.include("ChangeTooLarge");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("100");
+    });
+  });
+
+  describe("Time-Locked Increment", () => {
+    it("Should fail before the unlock time and succeed after", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+      const incrementAfter = () =>
+        program.methods
+          .incrementAfter(new anchor.BN(now + 2))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+      try {
+        await incrementAfter();
+        expect.fail("Expected too early error");
+      } catch (error) {
+        expect(error.message).to.include("TooEarly");
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      await incrementAfter();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Clone Counter", () => {
+    it("Should copy the value and settings into an independent counter", async () => {
+      const [sourcePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [clonePda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("clone"),
+          authorityKeypair.publicKey.toBuffer(),
+          Buffer.from([7]),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(12), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(50),
+          minValue: new anchor.BN(2),
+        })
+        .accounts({
+          counter: sourcePda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const delegate = anchor.web3.Keypair.generate().publicKey;
+      await program.methods
+        .setDailyCap(new anchor.BN(5))
+        .accounts({ counter: sourcePda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .addDelegate(delegate)
+        .accounts({ counter: sourcePda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .cloneCounter(7)
+        .accounts({
+          source: sourcePda,
+          counter: clonePda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const source = await program.account.counter.fetch(sourcePda);
+      let clone = await program.account.counter.fetch(clonePda);
+      expect(clone.count.toString()).to.equal("12");
+      expect(clone.maxValue.toString()).to.equal(source.maxValue.toString());
+      expect(clone.minValue.toString()).to.equal(source.minValue.toString());
+      expect(clone.dailyCap.toString()).to.equal("5");
+      expect(clone.delegates.map((key) => key.toString())).to.deep.equal([
+        delegate.toString(),
+      ]);
+      expect(clone.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: clonePda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      clone = await program.account.counter.fetch(clonePda);
+      const sourceAfter = await program.account.counter.fetch(sourcePda);
+      expect(clone.count.toString()).to.equal("13");
+      expect(sourceAfter.count.toString()).to.equal("12");
+    });
+  });
+
+  describe("Weighted Voting", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let mint: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      mint = await createMint(authorityKeypair.publicKey);
+      await program.methods
+        .openVoteRound(mint)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    // Give `voter` a funded token account holding `balance` tokens
+    const fundVoter = async (voter: anchor.web3.Keypair, balance: number) => {
+      const tokenAccount = await createTokenAccount(mint, voter.publicKey);
+      await mintTo(mint, tokenAccount, authorityKeypair, balance);
+      return tokenAccount;
+    };
+
+    const vote = (
+      voter: anchor.web3.Keypair,
+      tokenAccount: anchor.web3.PublicKey
+    ) =>
+      program.methods
+        .vote()
+        .accounts({
+          counter: counterPda,
+          voter: voter.publicKey,
+          tokenAccount,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([voter])
+        .rpc();
+
+    it("Should tally the balances of two token holders", async () => {
+      await vote(authorityKeypair, await fundVoter(authorityKeypair, 5));
+      await vote(newAuthorityKeypair, await fundVoter(newAuthorityKeypair, 7));
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("12");
+    });
+
+    it("Should reject a second vote in the same round", async () => {
+      await vote(newAuthorityKeypair, await fundVoter(newAuthorityKeypair, 5));
+
+      // Moving to a fresh token account does not allow voting again
+      try {
+        await vote(newAuthorityKeypair, await fundVoter(newAuthorityKeypair, 5));
+        expect.fail("Expected already voted error");
+      } catch (error) {
+        expect(error.message).to.include("AlreadyVoted");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+    });
+  });
+
+  describe("Tags", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let metaPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const tagAccounts = () => ({
+      counter: counterPda,
+      meta: metaPda,
+      authority: authorityKeypair.publicKey,
+    });
+
+    it("Should resize only the metadata account as tags come and go", async () => {
+      const counterSize = async () =>
+        (await provider.connection.getAccountInfo(counterPda)).data.length;
+      const metaSize = async () =>
+        (await provider.connection.getAccountInfo(metaPda)).data.length;
+      const [counterBefore, metaBefore] = [await counterSize(), await metaSize()];
+
+      for (const tag of ["web", "mobile", "beta"]) {
+        await program.methods
+          .addTag(tag)
+          .accounts(tagAccounts())
+          .signers([authorityKeypair])
+          .rpc();
+      }
+      await program.methods
+        .removeTag("mobile")
+        .accounts(tagAccounts())
+        .signers([authorityKeypair])
+        .rpc();
+
+      const meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.tags).to.deep.equal(["web", "beta"]);
+      expect(await counterSize()).to.equal(counterBefore);
+      // Each remaining tag costs a 4-byte length prefix plus its bytes
+      expect(await metaSize()).to.equal(metaBefore + (4 + 3) + (4 + 4));
+    });
+
+    it("Should reject removing a tag that is not present", async () => {
+      try {
+        await program.methods
+          .removeTag("missing")
+          .accounts(tagAccounts())
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected tag not found error");
+      } catch (error) {
+        expect(error.message).to.include("TagNotFound");
+      }
+    });
+
+    it("Should reject tag changes while the counter is paused", async () => {
+      await program.methods
+        .pause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .addTag("web")
+          .accounts(tagAccounts())
+          .signers([authorityKeypair])
+          .rpc();
+
+        expect.fail("Expected counter paused error");
+      } catch (error) {
+        expect(error.message).to.include("CounterPaused");
+      }
+    });
+  });
+
+  describe("Parity", () => {
+    it("Should report whether the count is even", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const parities = [];
+      for (let i = 0; i < 3; i++) {
+        parities.push(
+          await program.methods
+            .getParity()
+            .accounts({ counter: counterPda })
+            .view()
+        );
+
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      // Counts 0, 1 and 2
+      expect(parities).to.deep.equal([true, false, true]);
+    });
+  });
+
+  describe("Batch Transfer Authority", () => {
+    // Create three counters owned by `authorityKeypair` (two via keypairs)
+    const createCounters = async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counters = [counterPda];
+      for (let i = 0; i < 2; i++) {
+        const keypair = anchor.web3.Keypair.generate();
+        await program.methods
+          .initializeKeypair(new anchor.BN(0), defaultParams())
+          .accounts({
+            counter: keypair.publicKey,
+            authority: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([authorityKeypair, keypair])
+          .rpc();
+        counters.push(keypair.publicKey);
+      }
+      return counters;
+    };
+
+    const batchTransfer = (counters: anchor.web3.PublicKey[]) =>
+      program.methods
+        .batchTransferAuthority(newAuthorityKeypair.publicKey)
+        .accounts({ authority: authorityKeypair.publicKey })
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should move three counters to the new authority", async () => {
+      const counters = await createCounters();
+      await batchTransfer(counters);
+
+      for (const counter of counters) {
+        const counterAccount = await program.account.counter.fetch(counter);
+        expect(counterAccount.authority.toString()).to.equal(
+          newAuthorityKeypair.publicKey.toString()
+        );
+      }
+    });
+
+    it("Should abort when one counter has a different authority", async () => {
+      const counters = await createCounters();
+
+      const [foreignCounter] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), newAuthorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: foreignCounter,
+          authority: newAuthorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+      try {
+        await batchTransfer([...counters, foreignCounter]);
+        expect.fail("Expected unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counters[0]);
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Deadline Increment", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementBefore = (deadline: number) =>
+      program.methods
+        .incrementBefore(new anchor.BN(deadline))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should reject a deadline in the past", async () => {
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+
+      try {
+        await incrementBefore(now - 60);
+        expect.fail("Expected deadline exceeded error");
+      } catch (error) {
+        expect(error.message).to.include("DeadlineExceeded");
+      }
+    });
+
+    it("Should increment before a future deadline", async () => {
+      const now = await provider.connection.getBlockTime(
+        await provider.connection.getSlot()
+      );
+      await incrementBefore(now + 60);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Overflow Policy", () => {
+    const nearMax = new anchor.BN("18446744073709551614"); // u64::MAX - 1
+    let counterPda: anchor.web3.PublicKey;
+
+    // Create a counter one below u64::MAX and add five to it
+    const incrementPastMax = async (overflowPolicy: object) => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(nearMax, { ...defaultParams(), overflowPolicy })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .incrementBy(new anchor.BN(5))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    };
+
+    it("Should fail on overflow with the Error policy", async () => {
+      try {
+        await incrementPastMax({ error: {} });
+        expect.fail("Expected overflow error");
+      } catch (error) {
+        expect(error.message).to.include("Overflow");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal(nearMax.toString());
+    });
+
+    it("Should clamp at u64::MAX with the Saturate policy", async () => {
+      await incrementPastMax({ saturate: {} });
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("18446744073709551615");
+    });
+
+    it("Should roll over to the minimum with the Wrap policy", async () => {
+      await incrementPastMax({ wrap: {} });
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+  });
+
+  describe("Password Increment", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const passwordHash = createHash("sha256").update("hunter2").digest();
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          passwordHash: Array.from(passwordHash),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    // Called by a signer other than the authority
+    const incrementWithPassword = (password: string) =>
+      program.methods
+        .incrementWithPassword(password)
+        .accounts({
+          counter: counterPda,
+          signer: newAuthorityKeypair.publicKey,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+    it("Should increment with the correct password", async () => {
+      await incrementWithPassword("hunter2");
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+      expect(counterAccount.lastModifier.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+
+    it("Should reject an incorrect password", async () => {
+      try {
+        await incrementWithPassword("hunter3");
+        expect.fail("Expected wrong password error");
+      } catch (error) {
+        expect(error.message).to.include("WrongPassword");
+      }
+    });
+  });
+
+  describe("Global Stats", () => {
+    const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("config")],
+      program.programId
+    );
+
+    beforeEach(async () => {
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+    });
+
+    it("Should count creations and increments that pass the config", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      const before = await program.methods
+        .getGlobalStats()
+        .accounts({ config: configPda })
+        .view();
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (let i = 0; i < 2; i++) {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+            config: configPda,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const after = await program.methods
+        .getGlobalStats()
+        .accounts({ config: configPda })
+        .view();
+
+      expect(
+        after.totalCountersCreated.sub(before.totalCountersCreated).toNumber()
+      ).to.equal(1);
+      expect(
+        after.totalIncrements.sub(before.totalIncrements).toNumber()
+      ).to.equal(2);
+      expect(after.totalClosed.toString()).to.equal(
+        before.totalClosed.toString()
+      );
+    });
+
+    it("Should count increments made outside the Increment context", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const before = await program.methods
+        .getGlobalStats()
+        .accounts({ config: configPda })
+        .view();
+
+      await program.methods
+        .incrementCpi()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          config: configPda,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const after = await program.methods
+        .getGlobalStats()
+        .accounts({ config: configPda })
+        .view();
+      expect(
+        after.totalIncrements.sub(before.totalIncrements).toNumber()
+      ).to.equal(1);
+    });
+  });
+
+  describe("Compression", () => {
+    it("Should round-trip a counter through compress and decompress", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [compressedPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("compressed"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(42), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const compressTx = await program.methods
+        .compress()
+        .accounts({
+          counter: counterPda,
+          compressed: compressedPda,
+          authority: authorityKeypair.publicKey,
+          meta: null,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      expect(await program.account.counter.fetchNullable(counterPda)).to.be
+        .null;
+
+      const [event] = (await fetchEvents(compressTx)).filter(
+        (e) => e.name === "counterCompressed"
+      );
+
+      await program.methods
+        .decompress(event.data.state)
+        .accounts({
+          counter: counterPda,
+          compressed: compressedPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("42");
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+      expect(await program.account.compressedCounter.fetchNullable(compressedPda))
+        .to.be.null;
+    });
+  });
+
+  describe("Milestones", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setMilestones([
+          new anchor.BN(2),
+          new anchor.BN(0),
+          new anchor.BN(0),
+          new anchor.BN(0),
+        ])
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementAndCollect = async () => {
+      const tx = await program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+      return (await fetchEvents(tx)).filter(
+        (e) => e.name === "milestoneReached"
+      );
+    };
+
+    it("Should emit exactly one event when a milestone is crossed", async () => {
+      expect(await incrementAndCollect()).to.have.length(0);
+
+      const events = await incrementAndCollect();
+      expect(events).to.have.length(1);
+      expect(events[0].data.milestone.toString()).to.equal("2");
+    });
+
+    it("Should emit on the increment_by that crosses a milestone", async () => {
+      const tx = await program.methods
+        .incrementBy(new anchor.BN(5))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc({ commitment: "confirmed" });
+      const events = (await fetchEvents(tx)).filter(
+        (e) => e.name === "milestoneReached"
+      );
+      expect(events).to.have.length(1);
+      expect(events[0].data.count.toString()).to.equal("5");
+
+      // Already fired, so the next plain increment stays quiet
+      expect(await incrementAndCollect()).to.have.length(0);
+    });
+
+    it("Should not fire again when the milestone is re-crossed", async () => {
+      await incrementAndCollect();
+      expect(await incrementAndCollect()).to.have.length(1);
+
+      await program.methods
+        .decrement()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect(await incrementAndCollect()).to.have.length(0);
+    });
+  });
+
+  describe("Token Bucket", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setTokenBucket(new anchor.BN(2), new anchor.BN(1))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const increment = () =>
+      program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should allow a burst, throttle when empty, and recover after refill", async () => {
+      await increment();
+      await increment();
+
+      try {
+        await increment();
+        expect.fail("Expected rate limit error");
+      } catch (error) {
+        expect(error.message).to.include("RateLimited");
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      await increment();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+  });
+
+  describe("Raw Count Reader", () => {
+    it("Should read the count of a standard counter", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(37), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const count = await program.methods
+        .getCountRaw()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(count.toNumber()).to.equal(37);
+    });
+
+    it("Should reject an account with the wrong discriminator", async () => {
+      const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+      );
+      if (!(await program.account.config.fetchNullable(configPda))) {
+        await program.methods
+          .initializeConfig(new anchor.BN(0), new anchor.BN(0))
+          .accounts({
+            config: configPda,
+            admin: provider.publicKey,
+          })
+          .rpc();
+      }
+
+      try {
+        await program.methods
+          .getCountRaw()
+          .accounts({ counter: configPda })
+          .view();
+        expect.fail("Expected discriminator mismatch");
+      } catch (error) {
+        expect(error.message).to.include("AccountDiscriminatorMismatch");
+      }
+    });
+
+    it("Should reject an account owned by another program", async () => {
+      try {
+        await program.methods
+          .getCountRaw()
+          .accounts({ counter: authorityKeypair.publicKey })
+          .view();
+        expect.fail("Expected wrong owner error");
+      } catch (error) {
+        expect(error.message).to.include("AccountOwnedByWrongProgram");
+      }
+    });
+  });
+
+  describe("Sweep Excess", () => {
+    it("Should return lamports above the rent-exempt minimum", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const excess = anchor.web3.LAMPORTS_PER_SOL / 2;
+      await provider.sendAndConfirm(
+        new anchor.web3.Transaction().add(
+          anchor.web3.SystemProgram.transfer({
+            fromPubkey: provider.publicKey,
+            toPubkey: counterPda,
+            lamports: excess,
+          })
+        )
+      );
+
+      const swept = await simulateReturnData(
+        program.methods
+          .sweepExcess(null)
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+      );
+      expect(new anchor.BN(swept, "le").toNumber()).to.equal(excess);
+
+      await program.methods
+        .sweepExcess(null)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(counterPda);
+      const rent =
+        await provider.connection.getMinimumBalanceForRentExemption(
+          info.data.length
+        );
+      expect(info.lamports).to.equal(rent);
+    });
+
+    it("Should reject a sweep that would leave the counter below rent", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .sweepExcess(new anchor.BN(1))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected rent-exemption error");
+      } catch (error) {
+        expect(error.message).to.include("WouldBeNonRentExempt");
+      }
+    });
+  });
+
+  describe("Conditional Increment", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+    });
+
+    const incrementIfOther = (threshold: number) =>
+      program.methods
+        .incrementIfOther(new anchor.BN(threshold))
+        .accounts({
+          counter: counterPda,
+          other: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should increment when the other counter meets the threshold", async () => {
+      await incrementIfOther(5);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject when the other counter is below the threshold", async () => {
+      try {
+        await incrementIfOther(6);
+        expect.fail("Expected condition error");
+      } catch (error) {
+        expect(error.message).to.include("ConditionNotMet");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+  });
+
+  describe("Transfer Limit", () => {
+    it("Should allow transfers up to the cap and reject the next", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), { ...defaultParams(), maxTransfers: 1 })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .transferAuthority(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await program.methods
+          .transferAuthority(authorityKeypair.publicKey)
+          .accounts({
+            counter: counterPda,
+            authority: newAuthorityKeypair.publicKey,
+          })
+          .signers([newAuthorityKeypair])
+          .rpc();
+        expect.fail("Expected transfer limit error");
+      } catch (error) {
+        expect(error.message).to.include("TransferLimitReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.transferCount).to.equal(1);
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+
+    it("Should count accepted proposals against the cap", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), { ...defaultParams(), maxTransfers: 1 })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const handOff = async (
+        from: anchor.web3.Keypair,
+        to: anchor.web3.Keypair
+      ) => {
+        await program.methods
+          .proposeAuthority(to.publicKey)
+          .accounts({ counter: counterPda, authority: from.publicKey })
+          .signers([from])
+          .rpc();
+        await program.methods
+          .acceptAuthority()
+          .accounts({ counter: counterPda, newAuthority: to.publicKey })
+          .signers([to])
+          .rpc();
+      };
+
+      await handOff(authorityKeypair, newAuthorityKeypair);
+      try {
+        await handOff(newAuthorityKeypair, authorityKeypair);
+        expect.fail("Expected transfer limit error");
+      } catch (error) {
+        expect(error.message).to.include("TransferLimitReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.transferCount).to.equal(1);
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Random Increment", () => {
+    it("Should increment on some slots and miss on others", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const attempts = 20;
+      for (let i = 0; i < attempts; i++) {
+        await program.methods
+          .incrementRandom(new anchor.BN(2))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+            slotHashes: anchor.web3.SYSVAR_SLOT_HASHES_PUBKEY,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        // Let the slot (and blockhash) advance between attempts
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+
+      // Missing all 20 coin flips (or hitting all of them) is ~1 in a million
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toNumber()).to.be.greaterThan(0);
+      expect(counterAccount.count.toNumber()).to.be.lessThan(attempts);
+    });
+  });
+
+  describe("Ratio", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    const createCounters = async (numerator: number, denominator: number) => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(numerator), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(denominator), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+    };
+
+    const getRatio = () =>
+      program.methods
+        .getRatio()
+        .accounts({
+          numerator: counterPda,
+          denominator: counterKeypair.publicKey,
+        })
+        .view();
+
+    it("Should return 3/4 as 7500 basis points", async () => {
+      await createCounters(3, 4);
+
+      const ratio = await getRatio();
+      expect(ratio.toNumber()).to.equal(7500);
+    });
+
+    it("Should reject a zero denominator", async () => {
+      await createCounters(3, 0);
+
+      try {
+        await getRatio();
+        expect.fail("Expected divide by zero error");
+      } catch (error) {
+        expect(error.message).to.include("DivideByZero");
+      }
+    });
+  });
+
+  describe("Counter Expiry", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let registryPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [registryPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("registry"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setExpiresAfter(new anchor.BN(1))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    // Anyone may close an expired counter; use an unrelated signer
+    const closeExpired = () =>
+      program.methods
+        .closeExpired()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          closer: newAuthorityKeypair.publicKey,
+          meta: null,
+          registry: registryPda,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+    it("Should reject closing before the counter expires", async () => {
+      try {
+        await closeExpired();
+        expect.fail("Expected not expired error");
+      } catch (error) {
+        expect(error.message).to.include("NotExpired");
+      }
+    });
+
+    it("Should let any signer close it after expiry", async () => {
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      const balanceBefore = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      await closeExpired();
+      const balanceAfter = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+
+      expect(await program.account.counter.fetchNullable(counterPda)).to.be
+        .null;
+      expect(balanceAfter).to.be.greaterThan(balanceBefore);
+    });
+  });
+
+  describe("Increment With Bump", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let bump: number;
+
+    beforeEach(async () => {
+      [counterPda, bump] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementWithBump = (value: number) =>
+      program.methods
+        .incrementWithBump(value)
+        .accounts({
+          authority: authorityKeypair.publicKey,
+          counter: counterPda,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should increment with the canonical bump", async () => {
+      await incrementWithBump(bump);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should reject a non-canonical bump", async () => {
+      try {
+        await incrementWithBump((bump + 255) % 256);
+        expect.fail("Expected invalid bump error");
+      } catch (error) {
+        expect(error.message).to.include("InvalidBump");
+      }
+    });
+  });
+
+  describe("Groups", () => {
+    let groupPda: anchor.web3.PublicKey;
+    let counterPda: anchor.web3.PublicKey;
+    let outsiderKeypair: anchor.web3.Keypair;
+
+    beforeEach(async () => {
+      const groupId = new anchor.BN(1);
+      [groupPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("group"),
+          authorityKeypair.publicKey.toBuffer(),
+          groupId.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+      );
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      outsiderKeypair = anchor.web3.Keypair.generate();
+
+      await program.methods
+        .initializeGroup(groupId)
+        .accounts({
+          group: groupPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          group: groupPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(7), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          group: groupPda,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+
+      // Same authority, but created outside the group
+      await program.methods
+        .initializeKeypair(new anchor.BN(100), defaultParams())
+        .accounts({
+          counter: outsiderKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, outsiderKeypair])
+        .rpc();
+    });
+
+    const groupSum = (counters: anchor.web3.PublicKey[]) =>
+      program.methods
+        .groupSum()
+        .accounts({ group: groupPda })
+        .remainingAccounts(
+          counters.map((pubkey) => ({
+            pubkey,
+            isWritable: false,
+            isSigner: false,
+          }))
+        )
+        .view();
+
+    it("Should sum the counters belonging to the group", async () => {
+      const total = await groupSum([counterPda, counterKeypair.publicKey]);
+      expect(total.toString()).to.equal("12");
+    });
+
+    it("Should reject a counter from outside the group", async () => {
+      try {
+        await groupSum([counterPda, outsiderKeypair.publicKey]);
+        expect.fail("Expected group membership error");
+      } catch (error) {
+        expect(error.message).to.include("NotInGroup");
+      }
+    });
+  });
+
+  describe("Configure", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let metaPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          minIntervalSeconds: new anchor.BN(0),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should update only the fields that are set", async () => {
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .configure({
+          maxValue: new anchor.BN(50),
+          minIntervalSeconds: null,
+          label: "after",
+        })
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.maxValue.toString()).to.equal("50");
+      expect(counterAccount.minIntervalSeconds.toString()).to.equal("0");
+      const meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.label).to.equal("after");
+    });
+
+    it("Should require the metadata account to change the label", async () => {
+      try {
+        await program.methods
+          .configure({
+            maxValue: new anchor.BN(50),
+            minIntervalSeconds: null,
+            label: "after",
+          })
+          .accounts({
+            counter: counterPda,
+            meta: null,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected missing meta error");
+      } catch (error) {
+        expect(error.message).to.include("MissingMeta");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.maxValue.toString()).to.equal("0");
+    });
+  });
+
+  describe("Initialize With Bounty", () => {
+    it("Should create the counter and fund its vault in one call", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [vaultPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("bounty"), counterPda.toBuffer()],
+        program.programId
+      );
+      const vaultLamports = anchor.web3.LAMPORTS_PER_SOL / 10;
+
+      await program.methods
+        .initializeWithBounty(
+          new anchor.BN(0),
+          new anchor.BN(1000),
+          new anchor.BN(vaultLamports)
+        )
+        .accounts({
+          counter: counterPda,
+          bountyVault: vaultPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.bountyLamports.toString()).to.equal("1000");
+      expect(await provider.connection.getBalance(vaultPda)).to.equal(
+        vaultLamports
+      );
+    });
+  });
+
+  describe("Seconds Since Update", () => {
+    it("Should report roughly the time elapsed since the last change", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+
+      // Cluster time only ticks per slot, so allow some slack either way
+      const elapsed = await program.methods
+        .secondsSinceUpdate()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(elapsed.toNumber()).to.be.within(1, 6);
+    });
+  });
+
+  describe("Circuit Breaker", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setCircuitBreaker(2, new anchor.BN(60), new anchor.BN(2))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const increment = () =>
+      program.methods
+        .increment()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should trip after the threshold and reset after the cooldown", async () => {
+      await increment();
+      await increment();
+
+      try {
+        await increment();
+        expect.fail("Expected circuit breaker error");
+      } catch (error) {
+        expect(error.message).to.include("CircuitBreakerTripped");
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+      await increment();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+  });
+
+  describe("Increment And Transfer", () => {
+    it("Should increment and hand the counter over in one call", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(4), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .incrementAndTransfer(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Reward Mint", () => {
+    it("Should mint reward tokens to the signer on increment", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [mintAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("reward-authority"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const mint = await createMint(mintAuthority);
+      const tokenAccount = await createTokenAccount(
+        mint,
+        authorityKeypair.publicKey
+      );
+
+      await program.methods
+        .incrementMintReward(new anchor.BN(25))
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          mintAuthority,
+          mint,
+          tokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+
+      const balance = await provider.connection.getTokenAccountBalance(
+        tokenAccount
+      );
+      expect(balance.value.amount).to.equal("25");
+    });
+  });
+
+  describe("Private Readers", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(11), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setPrivate(true)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .grantReader(newAuthorityKeypair.publicKey)
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const getCountAs = (reader: anchor.web3.Keypair) =>
+      program.methods
+        .getCount()
+        .accounts({ counter: counterPda, reader: reader.publicKey })
+        .signers([reader])
+        .view();
+
+    it("Should let a granted reader read the count", async () => {
+      const count = await getCountAs(newAuthorityKeypair);
+      expect(count.toNumber()).to.equal(11);
+    });
+
+    it("Should reject a signer who is not a reader", async () => {
+      try {
+        await getCountAs(anchor.web3.Keypair.generate());
+        expect.fail("Expected reader error");
+      } catch (error) {
+        expect(error.message).to.include("NotAuthorizedReader");
+      }
+    });
+  });
+
+  describe("Batch Reset", () => {
+    let counters: anchor.web3.PublicKey[];
+
+    beforeEach(async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const extraKeypair = anchor.web3.Keypair.generate();
+
+      await program.methods
+        .initialize(new anchor.BN(3), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (const [keypair, value] of [
+        [counterKeypair, 5],
+        [extraKeypair, 8],
+      ] as [anchor.web3.Keypair, number][]) {
+        await program.methods
+          .initializeKeypair(new anchor.BN(value), defaultParams())
+          .accounts({
+            counter: keypair.publicKey,
+            authority: authorityKeypair.publicKey,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([authorityKeypair, keypair])
+          .rpc();
+      }
+
+      counters = [counterPda, counterKeypair.publicKey, extraKeypair.publicKey];
+    });
+
+    const batchReset = (pubkeys: anchor.web3.PublicKey[]) =>
+      program.methods
+        .batchReset()
+        .accounts({ authority: authorityKeypair.publicKey })
+        .remainingAccounts(
+          pubkeys.map((pubkey) => ({
+            pubkey,
+            isWritable: true,
+            isSigner: false,
+          }))
+        )
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should reset three counters at once", async () => {
+      await batchReset(counters);
+
+      for (const pubkey of counters) {
+        const counterAccount = await program.account.counter.fetch(pubkey);
+        expect(counterAccount.count.toString()).to.equal("0");
+      }
+    });
+
+    it("Should reject a batch with another authority's counter", async () => {
+      const [otherPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), newAuthorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      await program.methods
+        .initialize(new anchor.BN(9), defaultParams())
+        .accounts({
+          counter: otherPda,
+          authority: newAuthorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+      try {
+        await batchReset([...counters, otherPda]);
+        expect.fail("Expected unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+
+      // Nothing was reset, including the counters before the bad one
+      const counterAccount = await program.account.counter.fetch(counters[0]);
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+  });
+
+  describe("Pending Increment", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    it("Should apply a staged increment on finalize", async () => {
+      await program.methods
+        .stageIncrement()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+      expect(counterAccount.pendingDelta.toString()).to.equal("1");
+
+      // A second stage is rejected while one is pending
+      try {
+        await program.methods
+          .stageIncrement()
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected pending exists error");
+      } catch (error) {
+        expect(error.message).to.include("PendingExists");
+      }
+
+      await program.methods
+        .finalize()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("6");
+      expect(counterAccount.pendingDelta.toString()).to.equal("0");
+    });
+
+    it("Should discard a staged increment on cancel", async () => {
+      await program.methods
+        .stageIncrement()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .cancelPending()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+      expect(counterAccount.pendingDelta.toString()).to.equal("0");
+
+      try {
+        await program.methods
+          .finalize()
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected no pending increment error");
+      } catch (error) {
+        expect(error.message).to.include("NoPendingIncrement");
+      }
+    });
+  });
+
+  describe("Increment With Proof", () => {
+    const MEMO_PROGRAM_ID = new anchor.web3.PublicKey(
+      "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr"
+    );
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementWithProof = () =>
+      program.methods
+        .incrementWithProof()
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          instructions: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .signers([authorityKeypair]);
+
+    it("Should increment when a memo instruction precedes it", async () => {
+      const memoIx = new anchor.web3.TransactionInstruction({
+        programId: MEMO_PROGRAM_ID,
+        keys: [],
+        data: Buffer.from("not a bot"),
+      });
+
+      await incrementWithProof().preInstructions([memoIx]).rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+
+    it("Should fail without the memo instruction", async () => {
+      try {
+        await incrementWithProof().rpc();
+        expect.fail("Expected missing instruction error");
+      } catch (error) {
+        expect(error.message).to.include("MissingRequiredInstruction");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+    });
+  });
+
+  describe("Decimals", () => {
+    it("Should return the decimals set at init alongside the raw count", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(1250), { ...defaultParams(), decimals: 2 })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.decimals).to.equal(2);
+
+      const scaled = await program.methods
+        .getScaledCount()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(scaled.count.toString()).to.equal("1250");
+      expect(scaled.decimals).to.equal(2);
+    });
+  });
+
+  describe("Merge", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(4), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeKeypair(new anchor.BN(6), defaultParams())
+        .accounts({
+          counter: counterKeypair.publicKey,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair, counterKeypair])
+        .rpc();
+    });
+
+    it("Should add the source count into the destination and close the source", async () => {
+      await program.methods
+        .merge()
+        .accounts({
+          destination: counterPda,
+          source: counterKeypair.publicKey,
+          sourceMeta: null,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("10");
+
+      const sourceInfo = await provider.connection.getAccountInfo(
+        counterKeypair.publicKey
+      );
+      expect(sourceInfo).to.be.null;
+    });
+
+    it("Should reject merging a counter into itself", async () => {
+      try {
+        await program.methods
+          .merge()
+          .accounts({
+            destination: counterPda,
+            source: counterPda,
+            sourceMeta: null,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected same account error");
+      } catch (error) {
+        expect(error.message).to.include("SameAccount");
+      }
+    });
+  });
+
+  describe("Read And Count", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    const initialize = async (minIntervalSeconds: number) => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          minIntervalSeconds: new anchor.BN(minIntervalSeconds),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setCountOnRead(true)
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+    };
+
+    const readAndCount = () =>
+      program.methods
+        .readAndCount()
+        .accounts({ counter: counterPda, reader: provider.publicKey });
+
+    it("Should increment on every read and return the new value", async () => {
+      await initialize(0);
+
+      const returned = await simulateReturnData(readAndCount());
+      expect(new anchor.BN(returned, "le").toString()).to.equal("1");
+
+      await readAndCount().rpc();
+      await readAndCount().rpc();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("2");
+    });
+
+    it("Should reject a read inside the rate limit interval", async () => {
+      await initialize(60);
+
+      await readAndCount().rpc();
+      try {
+        await readAndCount().rpc();
+        expect.fail("Expected rate limit error");
+      } catch (error) {
+        expect(error.message).to.include("RateLimited");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+    });
+  });
+
+  describe("Seen Range", () => {
+    it("Should track the lowest and highest counts across sets", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(10), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (const value of [25, 3, 17]) {
+        await program.methods
+          .setCount(new anchor.BN(value))
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const range = await program.methods
+        .getSeenRange()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(range.minSeen.toString()).to.equal("3");
+      expect(range.maxSeen.toString()).to.equal("25");
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("17");
+    });
+
+    it("Should widen the range on resets too", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(10), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const accounts = { counter: counterPda, authority: authorityKeypair.publicKey };
+      await program.methods
+        .resetTo(new anchor.BN(40))
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .reset()
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+
+      const range = await program.methods
+        .getSeenRange()
+        .accounts({ counter: counterPda })
+        .view();
+      expect(range.minSeen.toString()).to.equal("0");
+      expect(range.maxSeen.toString()).to.equal("40");
+    });
+  });
+
+  describe("Escrow", () => {
+    const deposit = anchor.web3.LAMPORTS_PER_SOL;
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setEscrowTarget(new anchor.BN(2))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .fundEscrow(new anchor.BN(deposit))
+        .accounts({ counter: counterPda, funder: provider.publicKey })
+        .rpc();
+    });
+
+    const claimEscrow = () =>
+      program.methods
+        .claimEscrow()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+    const increment = () =>
+      program.methods
+        .increment()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should refuse to release the escrow below the target", async () => {
+      await increment();
+
+      try {
+        await claimEscrow();
+        expect.fail("Expected target not reached error");
+      } catch (error) {
+        expect(error.message).to.include("TargetNotReached");
+      }
+    });
+
+    it("Should release the escrow to the authority at the target", async () => {
+      await increment();
+      await increment();
+
+      const [escrowVault] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("escrow"), counterPda.toBuffer()],
+        program.programId
+      );
+      const balanceBefore = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+
+      await claimEscrow();
+
+      const balanceAfter = await provider.connection.getBalance(
+        authorityKeypair.publicKey
+      );
+      // The authority also pays the transaction fee
+      expect(balanceAfter - balanceBefore).to.be.greaterThan(deposit - 10000);
+      expect(await provider.connection.getBalance(escrowVault)).to.equal(0);
+    });
+
+    it("Should refuse to close a counter whose escrow is still funded", async () => {
+      try {
+        await program.methods
+          .close()
+          .accounts({
+            counter: counterPda,
+            authority: authorityKeypair.publicKey,
+            meta: null,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+        expect.fail("Expected escrow not empty error");
+      } catch (error) {
+        expect(error.message).to.include("EscrowNotEmpty");
+      }
+    });
+  });
+
+  describe("Migrate Seed", () => {
+    it("Should move the counter to the namespaced PDA and close the original", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [newCounterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("counter"),
+          Buffer.from("v2"),
+          authorityKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(42), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .migrateSeed("v2")
+        .accounts({
+          oldCounter: counterPda,
+          newCounter: newCounterPda,
+          authority: authorityKeypair.publicKey,
+          oldMeta: null,
+          newMeta: null,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(newCounterPda);
+      expect(counterAccount.count.toString()).to.equal("42");
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+
+      const oldInfo = await provider.connection.getAccountInfo(counterPda);
+      expect(oldInfo).to.be.null;
+    });
+
+    it("Should carry the metadata to the new address", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [newCounterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("counter"),
+          Buffer.from("v2"),
+          authorityKeypair.publicKey.toBuffer(),
+        ],
+        program.programId
+      );
+      const [oldMetaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+      const [newMetaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), newCounterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(42), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: oldMetaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .setLabel("signups")
+        .accounts({
+          counter: counterPda,
+          meta: oldMetaPda,
+          authority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .migrateSeed("v2")
+        .accounts({
+          oldCounter: counterPda,
+          newCounter: newCounterPda,
+          authority: authorityKeypair.publicKey,
+          oldMeta: oldMetaPda,
+          newMeta: newMetaPda,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const meta = await program.account.counterMeta.fetch(newMetaPda);
+      expect(meta.counter.toString()).to.equal(newCounterPda.toString());
+      expect(meta.label).to.equal("signups");
+      expect(await provider.connection.getAccountInfo(oldMetaPda)).to.be.null;
+    });
+  });
+
+  describe("Account Size", () => {
+    it("Should allocate exactly the serialized size of a full counter", async () => {
+      const counterLen = Number(
+        program.idl.constants.find((constant) => constant.name === "COUNTER_LEN")
+          .value
+      );
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const info = await provider.connection.getAccountInfo(counterPda);
+      expect(info.data.length).to.equal(counterLen);
+
+      // Fill every bounded field to its maximum
+      for (let i = 0; i < 8; i++) {
+        await program.methods
+          .grantReader(anchor.web3.Keypair.generate().publicKey)
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+      for (let i = 0; i < 8; i++) {
+        await program.methods
+          .scheduleIncrement(new anchor.BN(i))
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+      for (let i = 0; i < 5; i++) {
+        await program.methods
+          .addDelegate(anchor.web3.Keypair.generate().publicKey)
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      const encoded = await program.coder.accounts.encode("counter", counterAccount);
+      expect(encoded.length).to.equal(counterLen);
+    });
+  });
+
+  describe("Repair Timestamp", () => {
+    // Preloaded by Anchor.toml as a 56-byte counter with last_updated zeroed
+    const zeroedCounter = new anchor.web3.PublicKey(
+      "Gg5f4RJvBkwdKsWbeNmZ4GVKa9GTjkUVgW3iJrMEsZh"
+    );
+    const legacyAuthority = anchor.web3.Keypair.fromSecretKey(
+      Uint8Array.from(
+        JSON.parse(
+          fs.readFileSync("tests/fixtures/legacy-authority.json", "utf8")
+        )
+      )
+    );
+
+    const repairTimestamp = (
+      counter: anchor.web3.PublicKey,
+      authority: anchor.web3.Keypair
+    ) =>
+      program.methods
+        .repairTimestamp()
+        .accounts({ counter, authority: authority.publicKey })
+        .signers([authority])
+        .rpc();
+
+    it("Should set a zeroed last_updated to the current time", async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          legacyAuthority.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+      await program.methods
+        .migrate()
+        .accounts({
+          counter: zeroedCounter,
+          authority: legacyAuthority.publicKey,
+        })
+        .signers([legacyAuthority])
+        .rpc();
+
+      let counterAccount = await program.account.counter.fetch(zeroedCounter);
+      expect(counterAccount.lastUpdated.toNumber()).to.equal(0);
+
+      await repairTimestamp(zeroedCounter, legacyAuthority);
+
+      counterAccount = await program.account.counter.fetch(zeroedCounter);
+      const now = Math.floor(Date.now() / 1000);
+      expect(counterAccount.lastUpdated.toNumber()).to.be.within(now - 60, now + 60);
+      expect(counterAccount.count.toString()).to.equal("7");
+    });
+
+    it("Should reject a counter whose last_updated is already valid", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await repairTimestamp(counterPda, authorityKeypair);
+        expect.fail("Expected nothing to repair error");
+      } catch (error) {
+        expect(error.message).to.include("NothingToRepair");
+      }
+    });
+  });
+
+  describe("Reentrancy Guard", () => {
+    // Preloaded by Anchor.toml with `locked` set, the state a reentrant call
+    // observes while an outbound CPI is in flight
+    const lockedCounter = new anchor.web3.PublicKey(
+      "6KqWYKzS7L2mmi85FePsSxxkQRotXj6cGVcPt1rg7bW4"
+    );
+    const legacyAuthority = anchor.web3.Keypair.fromSecretKey(
+      Uint8Array.from(
+        JSON.parse(
+          fs.readFileSync("tests/fixtures/legacy-authority.json", "utf8")
+        )
+      )
+    );
+
+    it("Should reject a mutation while the counter is locked", async () => {
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          legacyAuthority.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+      // Bring the fixture up to the current layout first
+      await program.methods
+        .migrate()
+        .accounts({
+          counter: lockedCounter,
+          authority: legacyAuthority.publicKey,
+        })
+        .signers([legacyAuthority])
+        .rpc();
+
+      try {
+        await program.methods
+          .increment()
+          .accounts({
+            counter: lockedCounter,
+            authority: legacyAuthority.publicKey,
+          })
+          .signers([legacyAuthority])
+          .rpc();
+        expect.fail("Expected reentrancy error");
+      } catch (error) {
+        expect(error.message).to.include("Reentrancy");
+      }
+
+      const counterAccount = await program.account.counter.fetch(lockedCounter);
+      expect(counterAccount.count.toString()).to.equal("3");
+    });
+  });
+
+  describe("Timer", () => {
+    it("Should count down over time and grow when extended", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const getRemaining = async () =>
+        (
+          await program.methods
+            .getRemaining()
+            .accounts({ counter: counterPda })
+            .view()
+        ).toNumber();
+      const extend = (seconds: number) =>
+        program.methods
+          .extend(new anchor.BN(seconds))
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+
+      expect(await getRemaining()).to.equal(0);
+
+      await extend(100);
+      const started = await getRemaining();
+      expect(started).to.be.within(95, 100);
+
+      await new Promise((resolve) => setTimeout(resolve, 3000));
+      const later = await getRemaining();
+      expect(later).to.be.lessThan(started);
+
+      await extend(50);
+      expect(await getRemaining()).to.be.greaterThan(later + 40);
+    });
+  });
+
+  describe("Increment Max", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(5), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+    });
+
+    const incrementMax = (maxAcceptable: number) =>
+      program.methods
+        .incrementMax(new anchor.BN(maxAcceptable))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+    it("Should increment when the result is within the bound", async () => {
+      await incrementMax(6);
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("6");
+    });
+
+    it("Should reject an increment past the bound", async () => {
+      try {
+        await incrementMax(5);
+        expect.fail("Expected exceeds max acceptable error");
+      } catch (error) {
+        expect(error.message).to.include("ExceedsMaxAcceptable");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+    });
+  });
+
+  describe("Verify Count", () => {
+    it("Should report whether a claimed value matches the count", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(12), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const verify = (claimed: number) =>
+        program.methods
+          .verifyCount(new anchor.BN(claimed))
+          .accounts({ counter: counterPda })
+          .view();
+
+      expect(await verify(12)).to.equal(true);
+      expect(await verify(11)).to.equal(false);
+    });
+  });
+
+  describe("Daily Cap", () => {
+    it("Should reject increments once today's cap is used", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setDailyCap(new anchor.BN(2))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const increment = () =>
+        program.methods
+          .increment()
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+
+      await increment();
+      await increment();
+      try {
+        await increment();
+        expect.fail("Expected daily cap error");
+      } catch (error) {
+        expect(error.message).to.include("DailyCapReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("2");
+      expect(counterAccount.usedToday.toString()).to.equal("2");
+    });
+
+    it("Should hold cranked increments to the cap", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setDailyCap(new anchor.BN(2))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // Already due, so one crank tries to apply all three
+      const created = (await program.account.counter.fetch(counterPda))
+        .lastUpdated;
+      for (let i = 0; i < 3; i++) {
+        await program.methods
+          .scheduleIncrement(created)
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      try {
+        await program.methods
+          .crank()
+          .accounts({ counter: counterPda, cranker: provider.publicKey })
+          .rpc();
+        expect.fail("Expected daily cap error");
+      } catch (error) {
+        expect(error.message).to.include("DailyCapReached");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+      expect(counterAccount.scheduled.length).to.equal(3);
+    });
+
+    it("Should start a fresh allowance on a new day", async () => {
+      // Preloaded by Anchor.toml with the cap used up on a day in 2023
+      const exhaustedCounter = new anchor.web3.PublicKey(
+        "411qr6ViBPdZUFJUtdP4wLxu5kFR7pfChm4ompcb7gbt"
+      );
+      const legacyAuthority = anchor.web3.Keypair.fromSecretKey(
+        Uint8Array.from(
+          JSON.parse(
+            fs.readFileSync("tests/fixtures/legacy-authority.json", "utf8")
+          )
+        )
+      );
+      await provider.connection.confirmTransaction(
+        await provider.connection.requestAirdrop(
+          legacyAuthority.publicKey,
+          anchor.web3.LAMPORTS_PER_SOL
+        ),
+        "confirmed"
+      );
+      // Bring the fixture up to the current layout first
+      await program.methods
+        .migrate()
+        .accounts({
+          counter: exhaustedCounter,
+          authority: legacyAuthority.publicKey,
+        })
+        .signers([legacyAuthority])
+        .rpc();
+
+      await program.methods
+        .increment()
+        .accounts({
+          counter: exhaustedCounter,
+          authority: legacyAuthority.publicKey,
+        })
+        .signers([legacyAuthority])
+        .rpc();
+
+      const counterAccount = await program.account.counter.fetch(
+        exhaustedCounter
+      );
+      expect(counterAccount.count.toString()).to.equal("3");
+      expect(counterAccount.usedToday.toString()).to.equal("1");
+      expect(counterAccount.dayIndex.toNumber()).to.equal(
+        Math.floor(Date.now() / 1000 / 86400)
+      );
+    });
+  });
+
+  describe("Derive PDA", () => {
+    it("Should match the client-side derivation", async () => {
+      const authority = anchor.web3.Keypair.generate().publicKey;
+      const [expected, expectedBump] =
+        anchor.web3.PublicKey.findProgramAddressSync(
+          [Buffer.from("counter"), authority.toBuffer()],
+          program.programId
+        );
+
+      const derived = await program.methods.derivePda(authority).view();
+      expect(derived.address.toString()).to.equal(expected.toString());
+      expect(derived.bump).to.equal(expectedBump);
+    });
+  });
+
+  describe("Authority Transfer Timelock", () => {
+    let counterPda: anchor.web3.PublicKey;
+
+    const proposeWithTimelock = async (seconds: number) => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setTransferTimelock(new anchor.BN(seconds))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .proposeAuthority(newAuthorityKeypair.publicKey)
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+    };
+
+    const acceptAuthority = () =>
+      program.methods
+        .acceptAuthority()
+        .accounts({
+          counter: counterPda,
+          newAuthority: newAuthorityKeypair.publicKey,
+        })
+        .signers([newAuthorityKeypair])
+        .rpc();
+
+    it("Should reject acceptance before the timelock elapses", async () => {
+      await proposeWithTimelock(60);
+
+      try {
+        await acceptAuthority();
+        expect.fail("Expected timelock error");
+      } catch (error) {
+        expect(error.message).to.include("TimelockActive");
+      }
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        authorityKeypair.publicKey.toString()
+      );
+    });
+
+    it("Should accept once the timelock has elapsed", async () => {
+      await proposeWithTimelock(2);
+      await new Promise((resolve) => setTimeout(resolve, 4000));
+
+      await acceptAuthority();
+
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.authority.toString()).to.equal(
+        newAuthorityKeypair.publicKey.toString()
+      );
+    });
+  });
+
+  describe("Can Modify", () => {
+    it("Should allow the authority and delegate but nobody else", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const delegate = anchor.web3.Keypair.generate().publicKey;
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .setDelegate(delegate)
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const canModify = (who: anchor.web3.PublicKey) =>
+        program.methods
+          .canModify(who)
+          .accounts({ counter: counterPda })
+          .view();
+
+      expect(await canModify(authorityKeypair.publicKey)).to.equal(true);
+      expect(await canModify(delegate)).to.equal(true);
+      expect(await canModify(newAuthorityKeypair.publicKey)).to.equal(false);
+    });
+  });
+
+  describe("Last Slot", () => {
+    it("Should advance the stamped slot across increments", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const getLastSlot = async () =>
+        (
+          await program.methods
+            .getLastSlot()
+            .accounts({ counter: counterPda })
+            .view()
+        ).toNumber();
+      const increment = () =>
+        program.methods
+          .increment()
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+
+      const created = await getLastSlot();
+      expect(created).to.be.greaterThan(0);
+
+      await increment();
+      const first = await getLastSlot();
+      expect(first).to.be.greaterThan(created);
+
+      await increment();
+      expect(await getLastSlot()).to.be.greaterThan(first);
+    });
+  });
+
+  describe("Unit", () => {
+    it("Should round-trip every unit variant", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      for (const variant of ["count", "seconds", "bytes", "custom"]) {
+        const unit = { [variant]: {} };
+
+        await program.methods
+          .setMetaUnit(unit)
+          .accounts({
+            counter: counterPda,
+            meta: metaPda,
+            authority: authorityKeypair.publicKey,
+          })
+          .signers([authorityKeypair])
+          .rpc();
+
+        const meta = await program.account.counterMeta.fetch(metaPda);
+        expect(meta.unit).to.deep.equal(unit);
+
+        const returned = await program.methods
+          .getUnit()
+          .accounts({ counter: counterPda, meta: metaPda })
+          .view();
+        expect(returned).to.deep.equal(unit);
+      }
+    });
+  });
+
+  describe("Scheduled Increments", () => {
+    it("Should apply queued increments once they are due", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      // Schedule relative to cluster time rather than the local clock
+      const created = (await program.account.counter.fetch(counterPda))
+        .lastUpdated;
+      for (const offset of [2, 3]) {
+        await program.methods
+          .scheduleIncrement(created.addn(offset))
+          .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+          .signers([authorityKeypair])
+          .rpc();
+      }
+
+      const crank = () =>
+        program.methods
+          .crank()
+          .accounts({ counter: counterPda, cranker: provider.publicKey });
+
+      await crank().rpc();
+      let counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("0");
+      expect(counterAccount.scheduled.length).to.equal(2);
+
+      await new Promise((resolve) => setTimeout(resolve, 5000));
+
+      const applied = await simulateReturnData(crank());
+      expect(new anchor.BN(applied, "le").toString()).to.equal("2");
+      await crank().rpc();
+
+      counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("2");
+      expect(counterAccount.scheduled.length).to.equal(0);
+    });
+  });
+
+  describe("Delegates List", () => {
+    let counterPda: anchor.web3.PublicKey;
+    let delegates: anchor.web3.Keypair[];
+
+    const addDelegate = (delegate: anchor.web3.PublicKey) =>
+      program.methods
+        .addDelegate(delegate)
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+    beforeEach(async () => {
+      [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      delegates = Array.from({ length: 5 }, () => anchor.web3.Keypair.generate());
+      for (const delegate of delegates) {
+        await addDelegate(delegate.publicKey);
+      }
+    });
+
+    it("Should reject a delegate beyond the limit", async () => {
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.delegates.length).to.equal(5);
+
+      try {
+        await addDelegate(anchor.web3.Keypair.generate().publicKey);
+        expect.fail("Expected delegate limit error");
+      } catch (error) {
+        expect(error.message).to.include("DelegateLimitReached");
+      }
+    });
+
+    it("Should let a listed delegate increment until removed", async () => {
+      const delegate = delegates[3];
+      const incrementAsDelegate = () =>
+        program.methods
+          .increment()
+          .accounts({ counter: counterPda, authority: delegate.publicKey })
+          .signers([delegate])
+          .rpc();
+
+      await incrementAsDelegate();
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("1");
+
+      await program.methods
+        .removeDelegate(delegate.publicKey)
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      try {
+        await incrementAsDelegate();
+        expect.fail("Expected unauthorized error");
+      } catch (error) {
+        expect(error.message).to.include("Unauthorized");
+      }
+    });
+  });
+
+  describe("Simulate Increment", () => {
+    it("Should preview the wrapped value without storing it", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(4), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(5),
+          minValue: new anchor.BN(1),
+          wrap: true,
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const simulate = () =>
+        program.methods.simulateIncrement().accounts({ counter: counterPda }).view();
+
+      expect((await simulate()).toString()).to.equal("5");
+
+      await program.methods
+        .increment()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      expect((await simulate()).toString()).to.equal("1");
+      const counterAccount = await program.account.counter.fetch(counterPda);
+      expect(counterAccount.count.toString()).to.equal("5");
+    });
+  });
+
+  describe("Counter Meta", () => {
+    it("Should update metadata without touching the counter account", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [metaPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("meta"), counterPda.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(3), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      await program.methods
+        .initializeMeta()
+        .accounts({
+          counter: counterPda,
+          meta: metaPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      let meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.counter.toBase58()).to.equal(counterPda.toBase58());
+      expect(meta.label).to.equal("");
+
+      const before = (await provider.connection.getAccountInfo(counterPda)).data;
+
+      const accounts = {
+        counter: counterPda,
+        meta: metaPda,
+        authority: authorityKeypair.publicKey,
+      };
+      await program.methods
+        .setLabel("cold")
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .setNote("archived stats")
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .setMetaTags(["a", "b"])
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .addTag("c")
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .setMetaUnit({ seconds: {} })
+        .accounts(accounts)
+        .signers([authorityKeypair])
+        .rpc();
+
+      meta = await program.account.counterMeta.fetch(metaPda);
+      expect(meta.label).to.equal("cold");
+      expect(meta.note).to.equal("archived stats");
+      expect(meta.tags).to.deep.equal(["a", "b", "c"]);
+      expect(meta.unit).to.deep.equal({ seconds: {} });
+
+      const after = (await provider.connection.getAccountInfo(counterPda)).data;
+      expect(after.equals(before)).to.equal(true);
+    });
+  });
+
+  describe("Status", () => {
+    it("Should flag a paused counter at its cap", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), {
+          ...defaultParams(),
+          maxValue: new anchor.BN(1),
+          minIntervalSeconds: new anchor.BN(60),
+        })
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const getStatus = () =>
+        program.methods.getStatus().accounts({ counter: counterPda }).view();
+
+      let status = await getStatus();
+      expect(status.paused).to.equal(false);
+      expect(status.capReached).to.equal(false);
+      expect(status.rateLimited).to.equal(false);
+
+      await program.methods
+        .increment()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .pause()
+        .accounts({
+          counter: counterPda,
+          freezeAuthority: authorityKeypair.publicKey,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+
+      status = await getStatus();
+      expect(status.count.toString()).to.equal("1");
+      expect(status.paused).to.equal(true);
+      expect(status.archived).to.equal(false);
+      expect(status.rateLimited).to.equal(true);
+      expect(status.capReached).to.equal(true);
+    });
+
+    it("Should flag a used-up daily cap and the global pause", async () => {
+      const [counterPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), authorityKeypair.publicKey.toBuffer()],
+        program.programId
+      );
+      const [configPda] = anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("config")],
+        program.programId
+      );
+
+      await program.methods
+        .initialize(new anchor.BN(0), defaultParams())
+        .accounts({
+          counter: counterPda,
+          authority: authorityKeypair.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .setDailyCap(new anchor.BN(1))
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+      await program.methods
+        .increment()
+        .accounts({ counter: counterPda, authority: authorityKeypair.publicKey })
+        .signers([authorityKeypair])
+        .rpc();
+
+      const getStatus = () =>
+        program.methods
+          .getStatus()
+          .accounts({ counter: counterPda, config: configPda })
+          .view();
+      const setGlobalPause = (paused: boolean) =>
+        program.methods
+          .setGlobalPause(paused)
+          .accounts({ config: configPda, admin: provider.publicKey })
+          .rpc();
+
+      let status = await getStatus();
+      expect(status.rateLimited).to.equal(true);
+      expect(status.paused).to.equal(false);
+
+      await setGlobalPause(true);
+      try {
+        status = await getStatus();
+        expect(status.paused).to.equal(true);
+      } finally {
+        await setGlobalPause(false);
+      }
+    });
+  });
 });
\ No newline at end of file